@@ -9,6 +9,17 @@ fn criterion_benchmark(c: &mut Criterion) {
                 .generate_name()
         });
     });
+
+    let compiled = RNG::try_from(&Language::Fantasy)
+        .expect("Fantasy language file is valid")
+        .compile()
+        .expect("Fantasy compiles");
+    c.bench_function("RNG Fantasy load text", |b| {
+        b.iter(|| RNG::try_from(&Language::Fantasy));
+    });
+    c.bench_function("RNG Fantasy load compiled", |b| {
+        b.iter(|| RNG::from_compiled(&compiled));
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -1,23 +1,36 @@
 #![warn(clippy::pedantic)]
 #![cfg_attr(test, allow(clippy::unwrap_used))]
 
+mod rng_blend;
+mod rng_blocklist;
+mod rng_capitalization;
+mod rng_case;
+mod rng_compiled;
+mod rng_constraints;
+mod rng_diversity;
+mod rng_encoder;
+mod rng_enumerate;
+mod rng_error;
+mod rng_identify;
 mod rng_joiner;
+mod rng_load;
+mod rng_orthography;
+mod rng_phonology;
+mod rng_pseudonym;
+mod rng_registry;
+mod rng_segment;
+mod rng_selector;
+mod rng_stratified;
 mod rng_syllable;
 mod rng_syllables;
+mod rng_train;
+mod rng_translit;
 mod rng_weighted_rnd;
 
 #[macro_use]
 extern crate bitflags;
 extern crate log;
 
-#[derive(Debug, Eq, PartialEq)]
-pub enum RNGError {
-    GenerationError,
-    InvalidLanguageFile,
-    ParsingError,
-    ReadError,
-}
-
 use anyhow::Result;
 use rand::{
     distr::{Distribution, StandardUniform},
@@ -25,11 +38,34 @@ use rand::{
 };
 use rust_embed::RustEmbed;
 use std::fmt;
+use std::io::{BufRead, Read};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use titlecase::titlecase;
 
+pub use crate::rng_blend::Blender;
+pub use crate::rng_blocklist::{BlockMode, Blocklist};
+pub use crate::rng_capitalization::{BadCapitalization, Capitalization};
+pub use crate::rng_case::{BadNameCase, NameCase};
+pub use crate::rng_constraints::NameConstraints;
+pub use crate::rng_diversity::Diversity;
+pub use crate::rng_encoder::NameEncoder;
+pub use crate::rng_enumerate::NameEnumerator;
+pub use crate::rng_error::{RNGError, RNGErrorKind};
+pub use crate::rng_identify::Identification;
+use crate::rng_load::Loader;
+pub use crate::rng_load::{LoadIssue, LoadPolicy, LoadReport};
+pub use crate::rng_orthography::Orthography;
+pub use crate::rng_phonology::Phonology;
+use crate::rng_registry::same_name;
+pub use crate::rng_registry::{
+    LANGUAGES_PATH_ENV, LanguageEntry, LanguageRegistry, LanguageSource,
+};
+pub use crate::rng_segment::Segmentation;
+pub use crate::rng_selector::LanguageSelector;
 use crate::rng_syllable::{Classification, Syllable};
 use crate::rng_syllables::Syllables;
+pub use crate::rng_translit::{BadTransliteration, Transliteration};
 use crate::rng_weighted_rnd::{NORMAL_WEIGHT, SHORT_WEIGHT};
 
 /// RNG (Random Name Generator) is a library that generates random
@@ -53,70 +89,307 @@ pub struct RNG {
     pub prefixes: Syllables,
     pub centers: Syllables,
     pub suffixes: Syllables,
-    pub bad_syllables: Vec<String>,
+    /// How the syllables are spelled, when they are written in phonemes. See `RNG::in_script`.
+    pub orthography: Orthography,
+    /// How the names are capitalized.
+    pub capitalization: Capitalization,
 }
 
 impl RNG {
-    /// Use if you want to return the RNG entity, even if there are issues with some
-    /// of the syllables. Otherwise, use `RNG::try_from`.
+    /// Loads one of the built-in languages. Built-in languages are always loaded strictly, see
+    /// `LoadPolicy`.
     ///
     /// # Errors
     ///
     /// Errors out if the language file is not able to be processed correctly.
-    pub fn new(language: &Language) -> Result<RNG, RNG> {
-        let Ok(rng) = RNG::process(language) else {
-            return Err(RNG::empty(language.to_string()));
+    pub fn new(language: &Language) -> Result<RNG, RNGError> {
+        RNG::process(*language)
+    }
+
+    /// Loads either a text language file or a compiled one (see `RNG::compile`), failing on the
+    /// first line that is not a valid syllable. Compiled files are recognized by their header,
+    /// regardless of the file's extension. Text files are named after their path; compiled files
+    /// keep the name they were compiled with.
+    ///
+    /// # Errors
+    ///
+    /// Errors out if the file cannot be read or is not able to be processed correctly.
+    pub fn new_from_file(filename: String) -> Result<RNG, RNGError> {
+        RNG::load_file(filename, LoadPolicy::Strict).map(|(rng, _)| rng)
+    }
+
+    /// Creates an RNG from the contents of a language file held in memory, such as a definition
+    /// stored in a database.
+    ///
+    /// ```
+    /// use rnglib::RNG;
+    ///
+    /// let rng = RNG::new_from_str("Micro".to_string(), "-a\nb\n+c").unwrap();
+    ///
+    /// assert_eq!(rng.generate_name_by_count(3), "Abc");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors out on the first line that is not a valid syllable, or if there are no prefix,
+    /// center or suffix syllables.
+    pub fn new_from_str(name: String, s: &str) -> Result<RNG, RNGError> {
+        RNG::load(name, s, LoadPolicy::Strict).map(|(rng, _)| rng)
+    }
+
+    /// Builds a language out of a corpus of example words, such as a list of names. Each word is
+    /// split into syllables, one for each run of vowels, with the consonants in between going to
+    /// the following syllable as far as the clusters that start words in the corpus allow. The
+    /// first syllable of a word becomes a prefix, the last one a suffix and any others centers,
+    /// weighted by how often they turn up.
+    ///
+    /// A syllable is marked as only following vowels (`-v`) or consonants (`-c`), or only
+    /// preceding them (`+v` or `+c`), when all of at least two syllables seen next to it agree.
+    /// Words with a single syllable are left out. Write the result out with
+    /// `RNG::to_language_file`.
+    ///
+    /// ```
+    /// use rnglib::RNG;
+    ///
+    /// let corpus = "Valorian Caledor Menelar Taliesin Eldamar Aranel Galadon";
+    /// let rng = RNG::train("Highborn".to_string(), corpus).unwrap();
+    ///
+    /// println!("{}: {}", rng.name, rng.generate_name());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors out with `RNGErrorKind::GenerationError` if the corpus doesn't yield prefixes,
+    /// centers and suffixes that can be joined together.
+    pub fn train(name: String, corpus: &str) -> Result<RNG, RNGError> {
+        rng_train::train(name, corpus)
+    }
+
+    /// Reads a language, text or compiled, from any reader. The whole input is read before it is
+    /// processed; use `RNG::new_from_buf_reader` to process a text language line by line.
+    ///
+    /// # Errors
+    ///
+    /// Errors out with `RNGErrorKind::ReadError` if reading fails, or
+    /// `RNGErrorKind::InvalidLanguageFile` if the input is not a valid language.
+    pub fn new_from_reader(name: String, mut reader: impl Read) -> Result<RNG, RNGError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        RNG::new_from_buf_reader(name, bytes.as_slice())
+    }
+
+    /// Reads a language from a buffered reader, such as `std::io::stdin().lock()`. Text languages
+    /// are classified one line at a time as they are read.
+    ///
+    /// # Errors
+    ///
+    /// Errors out with `RNGErrorKind::ReadError` if reading fails, or
+    /// `RNGErrorKind::InvalidLanguageFile` if the input is not a valid language.
+    pub fn new_from_buf_reader(name: String, reader: impl BufRead) -> Result<RNG, RNGError> {
+        RNG::load_reader(name, reader, LoadPolicy::Strict).map(|(rng, _)| rng)
+    }
+
+    /// Loads a language from a string under an explicit `LoadPolicy`, returning a `LoadReport`
+    /// alongside it.
+    ///
+    /// ```
+    /// use rnglib::{LoadPolicy, RNG};
+    ///
+    /// let (rng, report) = RNG::load("Sloppy".to_string(), "-a\n!!\nb\n+c", LoadPolicy::Lenient).unwrap();
+    ///
+    /// assert_eq!(rng.syllables().len(), 3);
+    /// assert_eq!(report.skipped[0].line, Some(2));
+    /// assert!(RNG::load("Sloppy".to_string(), "-a\n!!\nb\n+c", LoadPolicy::Strict).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// In strict mode, errors out on the first line that is not a valid syllable, or if there
+    /// are no prefix, center or suffix syllables.
+    pub fn load(name: String, s: &str, policy: LoadPolicy) -> Result<(RNG, LoadReport), RNGError> {
+        let mut loader = Loader::new(name, policy);
+        loader.lines(s)?;
+        loader.finish()
+    }
+
+    /// Loads a text or compiled language file under an explicit `LoadPolicy`, returning a
+    /// `LoadReport` alongside it.
+    ///
+    /// # Errors
+    ///
+    /// Errors out if the file cannot be read, or, in strict mode, on the first line that is not a
+    /// valid syllable or if there are no prefix, center or suffix syllables.
+    pub fn load_file(filename: String, policy: LoadPolicy) -> Result<(RNG, LoadReport), RNGError> {
+        let path = PathBuf::from(filename.as_str());
+        RNG::load_path(
+            &path,
+            filename,
+            policy,
+            Vec::new(),
+            &LanguageRegistry::from_env(),
+        )
+    }
+
+    /// Loads a language file, named `filename`, on behalf of the files in `chain`, which inherit
+    /// from it. Parents named by `@extends` are looked up in the registry.
+    fn load_path(
+        path: &Path,
+        filename: String,
+        policy: LoadPolicy,
+        chain: Vec<String>,
+        registry: &LanguageRegistry,
+    ) -> Result<(RNG, LoadReport), RNGError> {
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) => return Err(RNGError::from(e).with_file(filename.as_str())),
         };
 
-        if rng.is_valid() { Ok(rng) } else { Err(rng) }
+        if rng_compiled::is_compiled(&bytes) {
+            let rng = RNG::from_compiled(&bytes).map_err(|e| e.with_file(filename.as_str()))?;
+            Loader::from_rng(rng, policy).finish()
+        } else {
+            let file = filename.clone();
+            let loader = Loader::new(filename, policy)
+                .with_origin(path, chain)
+                .with_registry(registry.clone());
+            RNG::load_text(&bytes, loader).map_err(|e| e.with_file(file.as_str()))
+        }
     }
 
+    /// Reads a text or compiled language from a buffered reader under an explicit `LoadPolicy`,
+    /// returning a `LoadReport` alongside it.
+    ///
     /// # Errors
     ///
-    /// Errors out if the language file is not able to be processed correctly.
-    pub fn new_from_file(filename: String) -> Result<RNG, RNGError> {
-        match std::fs::read_to_string(filename.clone()) {
-            Ok(f) => match std::str::from_utf8(f.as_ref()) {
-                Ok(s) => Ok(RNG::classify(s, filename)),
-                Err(_) => Err(RNGError::InvalidLanguageFile),
-            },
-            Err(_) => Err(RNGError::InvalidLanguageFile),
+    /// Errors out if reading fails, or, in strict mode, on the first line that is not a valid
+    /// syllable or if there are no prefix, center or suffix syllables.
+    pub fn load_reader(
+        name: String,
+        mut reader: impl BufRead,
+        policy: LoadPolicy,
+    ) -> Result<(RNG, LoadReport), RNGError> {
+        // A reader can hand back fewer bytes than the header at a time, such as a pipe, so
+        // gather them up before looking, then read on from the start again.
+        let mut head = Vec::with_capacity(rng_compiled::MAGIC.len());
+        while head.len() < rng_compiled::MAGIC.len() {
+            let available = reader.fill_buf()?;
+            if available.is_empty() {
+                break;
+            }
+            let taken = available.len().min(rng_compiled::MAGIC.len() - head.len());
+            head.extend_from_slice(&available[..taken]);
+            reader.consume(taken);
+        }
+        let mut reader = head.as_slice().chain(reader);
+
+        if rng_compiled::is_compiled(&head) {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes)?;
+            let mut rng = RNG::from_compiled(&bytes)?;
+            rng.name = name;
+            return Loader::from_rng(rng, policy).finish();
+        }
+
+        let mut loader = Loader::new(name, policy);
+        for (index, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| RNGError::from(e).with_line(index + 1))?;
+            loader.line(line.as_str())?;
+        }
+        loader.finish()
+    }
+
+    /// Loads a language from the compact binary format produced by `RNG::compile`. No regular
+    /// expressions are run; the syllables are read back exactly as they were classified.
+    ///
+    /// # Errors
+    ///
+    /// Errors out if the bytes are not a compiled language file of a supported version.
+    pub fn from_compiled(bytes: &[u8]) -> Result<RNG, RNGError> {
+        rng_compiled::load(bytes)
+    }
+
+    /// Serializes the already classified syllables into the compact binary format, for fast
+    /// loading with `RNG::from_compiled` or `RNG::new_from_file`.
+    ///
+    /// # Errors
+    ///
+    /// Errors out with `RNGErrorKind::WriteError` if a syllable is 128 bytes or longer, or the
+    /// name, a phoneme or a spelling is 64KiB or longer.
+    pub fn compile(&self) -> Result<Vec<u8>, RNGError> {
+        rng_compiled::compile(self)
+    }
+
+    /// Compiles a text language file and writes the result to `output`.
+    ///
+    /// # Errors
+    ///
+    /// Errors out if the input is not a valid language file or the output cannot be written.
+    pub fn compile_file(input: &str, output: &str) -> Result<RNG, RNGError> {
+        let rng = RNG::new_from_file(input.to_string())?;
+        if !rng.is_valid() {
+            return Err(RNGError::new(RNGErrorKind::InvalidLanguageFile).with_file(input));
         }
+        let bytes = rng.compile().map_err(|e| e.with_file(input))?;
+        std::fs::write(output, bytes).map_err(|e| {
+            RNGError::new(RNGErrorKind::WriteError)
+                .with_file(output)
+                .with_source(e)
+        })?;
+        Ok(rng)
+    }
+
+    /// Loads the variant of a built-in language that best matches a locale, such as `ru` or
+    /// `ru-RU`, falling back to `DEFAULT_LOCALE` and then to the language itself. See
+    /// `Language::for_locale`.
+    ///
+    /// ```
+    /// use rnglib::{Language, RNG};
+    ///
+    /// let rng = RNG::for_locale(&Language::Elven, "ru-RU").unwrap();
+    ///
+    /// assert_eq!(rng.name, "Эльфийский");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors out if the language file is not able to be processed correctly.
+    pub fn for_locale(language: &Language, locale: &str) -> Result<RNG, RNGError> {
+        RNG::process(language.for_locale(locale))
     }
 
+    /// Loads a language picked by the default `LanguageSelector`: any of the production-ready
+    /// built-in languages, in its `DEFAULT_LOCALE` variant.
+    ///
     /// # Panics
     ///
     /// Panics if the embedded language file for the chosen language cannot be
     /// loaded, which cannot happen with the `debug-embed` feature enabled.
     #[must_use]
     pub fn random() -> RNG {
-        let my_dialect_type: Language = rand::random();
-        RNG::process(&my_dialect_type).expect("embedded language files are always present")
+        LanguageSelector::default()
+            .random()
+            .expect("embedded language files are always present")
     }
 
-    fn process(language: &Language) -> Result<RNG, RNGError> {
-        let txt =
-            Asset::get(language.get_filename().as_str()).ok_or(RNGError::InvalidLanguageFile)?;
-        let lines =
-            std::str::from_utf8(txt.data.as_ref()).map_err(|_| RNGError::InvalidLanguageFile)?;
-        Ok(RNG::classify(lines, language.to_string()))
+    fn process(language: Language) -> Result<RNG, RNGError> {
+        let filename = language.get_filename();
+        let txt = Asset::get(filename.as_str()).ok_or_else(|| {
+            RNGError::new(RNGErrorKind::InvalidLanguageFile).with_file(filename.as_str())
+        })?;
+        let loader = Loader::new(language.to_string(), LoadPolicy::Strict);
+        RNG::load_text(txt.data.as_ref(), loader)
+            .map(|(rng, _)| rng)
+            .map_err(|e| e.with_file(filename.as_str()))
     }
 
-    fn classify(lines: &str, name: String) -> RNG {
-        let mut rng = RNG::empty(name);
-
-        for line in lines.lines() {
-            if let Ok(sy) = Syllable::from_str(line) {
-                match sy.classification {
-                    Classification::Prefix => rng.prefixes.add(sy),
-                    Classification::Center => rng.centers.add(sy),
-                    Classification::Suffix => rng.suffixes.add(sy),
-                }
-            } else {
-                rng.bad_syllables.push(line.to_string());
-            }
-        }
-        rng
+    fn load_text(bytes: &[u8], mut loader: Loader) -> Result<(RNG, LoadReport), RNGError> {
+        let lines = std::str::from_utf8(bytes).map_err(|e| {
+            // Point the error at the line holding the first invalid byte.
+            let line = bytes[..e.valid_up_to()].split(|b| *b == b'\n').count();
+            RNGError::from(e).with_line(line)
+        })?;
+        loader.lines(lines)?;
+        loader.finish()
     }
 
     #[must_use]
@@ -126,7 +399,8 @@ impl RNG {
             prefixes: Syllables::new(),
             centers: Syllables::new(),
             suffixes: Syllables::new(),
-            bad_syllables: Vec::new(),
+            orthography: Orthography::new(),
+            capitalization: Capitalization::default(),
         }
     }
 
@@ -136,7 +410,6 @@ impl RNG {
             && self.prefixes.is_empty()
             && self.centers.is_empty()
             && self.suffixes.is_empty()
-            && self.bad_syllables.is_empty()
     }
 
     #[must_use]
@@ -145,7 +418,6 @@ impl RNG {
             && !self.prefixes.is_empty()
             && !self.centers.is_empty()
             && !self.suffixes.is_empty()
-            && self.bad_syllables.is_empty()
     }
 
     #[must_use]
@@ -170,6 +442,107 @@ impl RNG {
         v
     }
 
+    /// Walks through every name the language can make with a number of syllables in the range,
+    /// such as `2..=3`. See `NameEnumerator`.
+    #[must_use]
+    pub fn enumerate_names(&self, syllable_counts: RangeInclusive<u8>) -> NameEnumerator<'_> {
+        NameEnumerator::new(self, syllable_counts)
+    }
+
+    /// Creates a `NameEncoder` that turns numbers into names in this language and back.
+    ///
+    /// # Errors
+    ///
+    /// Errors out with `RNGErrorKind::GenerationError` if the language can't make a different
+    /// name for every `u64`.
+    pub fn encoder(&self) -> Result<NameEncoder<'_>, RNGError> {
+        NameEncoder::new(self)
+    }
+
+    /// Every way the name, ignoring case, can be split into syllables of the language that join
+    /// up with each other, most likely first. A name the language couldn't have made has none.
+    /// Since a long name can be split in a great many ways, at most 1000 are returned.
+    ///
+    /// ```
+    /// use rnglib::{Language, RNG};
+    ///
+    /// let rng = RNG::try_from(&Language::Goblin).unwrap();
+    ///
+    /// let segmentations = rng.segment("Krazubyr");
+    ///
+    /// assert_eq!(segmentations.len(), 2);
+    /// assert_eq!(segmentations[0].syllables, vec!["kra", "zu", "byr"]);
+    /// assert_eq!(segmentations[1].syllables, vec!["kra", "zub", "yr"]);
+    /// assert!(rng.segment("Galadriel").is_empty());
+    /// ```
+    #[must_use]
+    pub fn segment(&self, name: &str) -> Vec<Segmentation> {
+        rng_segment::segment(self, name)
+            .into_iter()
+            .map(|(syllables, probability)| Segmentation {
+                syllables: syllables.iter().map(|s| s.value.clone()).collect(),
+                probability,
+            })
+            .collect()
+    }
+
+    /// How likely the language is to generate the name, ignoring case: the chance of each way of
+    /// splitting it into syllables, weighted by how often names get that many syllables. Zero if
+    /// the language couldn't have made it.
+    #[must_use]
+    pub fn likelihood(&self, name: &str) -> f64 {
+        rng_identify::likelihood(self, name)
+    }
+
+    /// Scores the name against each of the languages, most likely first, leaving out the ones
+    /// that couldn't have made it. See `LanguageRegistry::identify` to score it against every
+    /// language available.
+    ///
+    /// ```
+    /// use rnglib::{Language, RNG};
+    ///
+    /// let languages = [
+    ///     RNG::try_from(&Language::Elven).unwrap(),
+    ///     RNG::try_from(&Language::Goblin).unwrap(),
+    /// ];
+    ///
+    /// let found = RNG::identify("Krazubyr", &languages);
+    ///
+    /// assert_eq!(found[0].language, "Goblin");
+    /// ```
+    #[must_use]
+    pub fn identify(name: &str, languages: &[RNG]) -> Vec<Identification> {
+        rng_identify::identify(name, languages)
+    }
+
+    /// Returns a vector of names that spreads out over the language's prefixes and suffixes,
+    /// using each one before any of them is used again, rather than picking each name's
+    /// syllables independently.
+    ///
+    /// ```
+    /// use rnglib::{Language, RNG};
+    /// use std::collections::HashSet;
+    ///
+    /// let rng = RNG::try_from(&Language::Goblin).unwrap();
+    /// let names = rng.generate_names_stratified(rng.prefixes.len(), false);
+    ///
+    /// let first_letters: HashSet<char> = names.iter().filter_map(|n| n.chars().next()).collect();
+    /// assert!(first_letters.len() > 1);
+    /// ```
+    #[must_use]
+    pub fn generate_names_stratified(&self, number: usize, is_short: bool) -> Vec<String> {
+        let mut stratified = rng_stratified::Stratified::new(self);
+        let weight = if is_short {
+            &SHORT_WEIGHT
+        } else {
+            &NORMAL_WEIGHT
+        };
+
+        (0..number)
+            .map(|_| self.finish_name(&stratified.syllables(weight.random())))
+            .collect()
+    }
+
     #[must_use]
     pub fn generate_names_string(&self, n: usize, is_short: bool) -> String {
         self.generate_names(n, is_short).join(" ")
@@ -182,8 +555,44 @@ impl RNG {
 
     #[must_use]
     pub fn generate_name_by_count(&self, count: u8) -> String {
-        let name = self.generate_syllables_by_count(count).collapse();
-        titlecase(name.as_str())
+        self.finish_name(&self.generate_syllables_by_count(count))
+    }
+
+    /// Generates a name that satisfies the constraints, such as an Elven name starting with S
+    /// that is five to eight letters long.
+    ///
+    /// # Errors
+    ///
+    /// Errors out with `RNGErrorKind::GenerationError` if the language can't make a name that
+    /// satisfies the constraints.
+    pub fn generate_name_with(&self, constraints: &NameConstraints) -> Result<String, RNGError> {
+        rng_constraints::generate(self, constraints, &[], &mut rand::rng())
+            .map(|syllables| self.finish_name(&syllables))
+    }
+
+    /// Returns a vector of names that satisfy the constraints. With a `Diversity` constraint,
+    /// no two of them are too alike.
+    ///
+    /// # Errors
+    ///
+    /// Errors out with `RNGErrorKind::GenerationError` if the language can't make enough names
+    /// that satisfy the constraints.
+    pub fn generate_names_with(
+        &self,
+        number: usize,
+        constraints: &NameConstraints,
+    ) -> Result<Vec<String>, RNGError> {
+        let mut rng = rand::rng();
+        let mut names = Vec::with_capacity(number);
+        for _ in 0..number {
+            let syllables = rng_constraints::generate(self, constraints, &names, &mut rng)?;
+            names.push(self.finish_name(&syllables));
+        }
+        Ok(names)
+    }
+
+    pub(crate) fn finish_name(&self, syllables: &Syllables) -> String {
+        self.capitalization.apply(syllables.collapse().as_str())
     }
 
     #[must_use]
@@ -196,11 +605,66 @@ impl RNG {
     /// Panics if the RNG's prefix, center, or suffix syllable sets are empty
     /// or contain no syllable compatible with the one before it.
     #[must_use]
-    pub fn generate_syllables_by_count(&self, mut syllable_count: u8) -> Syllables {
+    pub fn generate_syllables_by_count(&self, syllable_count: u8) -> Syllables {
+        self.generate_syllables_by_count_with_rng(syllable_count, &mut rand::rng())
+    }
+
+    /// Generates a name using the random number generator passed in, so that a seeded generator
+    /// always generates the same name.
+    ///
+    /// ```
+    /// use rand::SeedableRng;
+    /// use rand::rngs::Xoshiro256PlusPlus;
+    /// use rnglib::{Language, RNG};
+    ///
+    /// let rng = RNG::try_from(&Language::Roman).unwrap();
+    ///
+    /// let first = rng.generate_name_with_rng(&mut Xoshiro256PlusPlus::seed_from_u64(7));
+    /// let second = rng.generate_name_with_rng(&mut Xoshiro256PlusPlus::seed_from_u64(7));
+    ///
+    /// assert_eq!(first, second);
+    /// ```
+    pub fn generate_name_with_rng<R: Rng + ?Sized>(&self, rng: &mut R) -> String {
+        let count = NORMAL_WEIGHT.random_with(rng);
+        self.finish_name(&self.generate_syllables_by_count_with_rng(count, rng))
+    }
+
+    /// Generates a stable pseudonym for the input, such as a user ID: the same key and input
+    /// always give the same name in the same language. The input is hashed with the secret key
+    /// (HMAC-SHA256) and the hash seeds the generator, so the pseudonym is an ordinary name for
+    /// the language, and the input can't be worked out from it without the key.
+    ///
+    /// Like any generated names, two inputs can be given the same pseudonym. Where pseudonyms
+    /// must be unique, use `NameEncoder::pseudonym`, which gives longer names.
+    ///
+    /// ```
+    /// use rnglib::{Language, RNG};
+    ///
+    /// let rng = RNG::try_from(&Language::Goblin).unwrap();
+    ///
+    /// let name = rng.pseudonym(b"secret", "player-1234");
+    ///
+    /// assert_eq!(name, rng.pseudonym(b"secret", "player-1234"));
+    /// ```
+    #[must_use]
+    pub fn pseudonym(&self, key: &[u8], input: &str) -> String {
+        let mut rng = rand::rngs::Xoshiro256PlusPlus::from_seed(rng_pseudonym::seed(key, input));
+        self.generate_name_with_rng(&mut rng)
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the RNG's prefix, center, or suffix syllable sets are empty
+    /// or contain no syllable compatible with the one before it.
+    pub fn generate_syllables_by_count_with_rng<R: Rng + ?Sized>(
+        &self,
+        mut syllable_count: u8,
+        rng: &mut R,
+    ) -> Syllables {
         let mut syllables = Syllables::new();
         let mut last = self
             .prefixes
-            .get_random()
+            .get_random_with(rng)
             .expect("language has at least one prefix syllable")
             .clone();
         syllables.add(last.clone());
@@ -208,7 +672,7 @@ impl RNG {
         while syllable_count > 2 {
             let center_syllables = self.centers.filter_from(last.jnext);
             last = center_syllables
-                .get_random()
+                .get_random_with(rng)
                 .expect("language has a center syllable compatible with the previous one")
                 .clone();
             syllables.add(last.clone());
@@ -219,7 +683,7 @@ impl RNG {
 
         syllables.add(
             last_syllables
-                .get_random()
+                .get_random_with(rng)
                 .expect("language has a suffix syllable compatible with the previous one")
                 .clone(),
         );
@@ -227,6 +691,94 @@ impl RNG {
         syllables
     }
 
+    /// Writes the language out in the text language file format, one syllable per line, so that
+    /// generated or blended languages can be saved and loaded like any other. Any capitalization
+    /// other than the default and any spellings come first, as `@capitalize` and `@spell`
+    /// directives.
+    #[must_use]
+    pub fn to_language_file(&self) -> String {
+        let mut s = String::new();
+        if self.capitalization != Capitalization::default() {
+            s.push_str("@capitalize ");
+            s.push_str(&self.capitalization.to_string());
+            s.push('\n');
+        }
+        for (script, phoneme, spelling) in self.orthography.spellings() {
+            s.push_str("@spell ");
+            s.push_str(&script.to_string().to_lowercase());
+            s.push(' ');
+            s.push_str(phoneme);
+            s.push(' ');
+            s.push_str(spelling);
+            s.push('\n');
+        }
+        for syllable in self.syllables().all() {
+            s.push_str(&syllable.to_string());
+            s.push('\n');
+        }
+        s
+    }
+
+    /// The language as it is written in the script, with every syllable spelled out using its
+    /// `Orthography`. Joiners and weights are kept as they are, and the result has no orthography
+    /// of its own, so it generates names in the script directly.
+    ///
+    /// ```
+    /// use rnglib::{RNG, Script};
+    ///
+    /// let phonemic = "@spell latin ʃ sh\n@spell cyrillic ʃ ш\n@spell cyrillic a а\n-ʃa\na\n+ʃa";
+    /// let rng = RNG::new_from_str("Hush".to_string(), phonemic).unwrap();
+    ///
+    /// assert_eq!(rng.in_script(Script::Latin).generate_name_by_count(2), "Shasha");
+    /// assert_eq!(rng.in_script(Script::Cyrillic).generate_name_by_count(2), "Шаша");
+    /// ```
+    #[must_use]
+    pub fn in_script(&self, script: Script) -> RNG {
+        let mut rng = self.respelled(|syllable| self.orthography.spell(&syllable.value, script));
+        rng.orthography = Orthography::new();
+        rng
+    }
+
+    /// The language with any Cyrillic in its syllables written in Latin letters, following the
+    /// `Transliteration` standard, so that every name it generates comes out transliterated.
+    /// Joiners and weights are kept as they are.
+    ///
+    /// Syllables are transliterated one at a time, so a BGN/PCGN `е` that starts a center or
+    /// suffix is always written `e`, even after a vowel, where a whole name would have `ye`. For
+    /// exact results, generate names first and `Transliteration::transliterate` each one.
+    ///
+    /// ```
+    /// use rnglib::{Language, RNG, Transliteration};
+    ///
+    /// let rng = RNG::try_from(&Language::Гоблин).unwrap().transliterated(Transliteration::Gost);
+    ///
+    /// assert!(rng.generate_name().is_ascii());
+    /// ```
+    #[must_use]
+    pub fn transliterated(&self, standard: Transliteration) -> RNG {
+        self.respelled(|syllable| {
+            let within = syllable.classification != Classification::Prefix;
+            standard.transliterate_within(&syllable.value, within)
+        })
+    }
+
+    /// A copy of the language with every syllable's value replaced by what `spell` makes of it.
+    fn respelled(&self, spell: impl Fn(&Syllable) -> String) -> RNG {
+        let mut rng = self.clone();
+        for syllables in [&mut rng.prefixes, &mut rng.centers, &mut rng.suffixes] {
+            let spelled = syllables
+                .all()
+                .iter()
+                .map(|syllable| Syllable {
+                    value: spell(syllable),
+                    ..syllable.clone()
+                })
+                .collect();
+            *syllables = Syllables::new_from_vector(spelled);
+        }
+        rng
+    }
+
     #[must_use]
     pub fn syllables(&self) -> Syllables {
         let v = [
@@ -243,10 +795,13 @@ impl TryFrom<&Language> for RNG {
     type Error = RNGError;
 
     fn try_from(language: &Language) -> Result<Self, Self::Error> {
-        RNG::process(language)
+        RNG::process(*language)
     }
 }
 
+/// File extension used for compiled language files.
+pub const COMPILED_EXTENSION: &str = rng_compiled::EXTENSION;
+
 #[derive(RustEmbed)]
 #[folder = "src/languages/"]
 struct Asset;
@@ -256,13 +811,13 @@ struct Asset;
 mod lib_tests {
     use super::*;
     use proptest::prelude::*;
+    use std::error::Error;
 
     #[test]
     fn try_from() {
         let rng = RNG::try_from(&Language::Fantasy).unwrap();
 
         assert_eq!(rng.name, Language::Fantasy.to_string());
-        assert!(rng.bad_syllables.len() < 1);
         assert!(rng.prefixes.len() > 0);
         assert!(rng.centers.len() > 0);
         assert!(rng.suffixes.len() > 0);
@@ -272,7 +827,6 @@ mod lib_tests {
     fn try_from__demonic() {
         let rng = RNG::new(&Language::Demonic).unwrap();
 
-        assert!(rng.bad_syllables.len() < 1);
         assert!(rng.prefixes.len() > 0);
         assert!(rng.centers.len() > 0);
         assert!(rng.suffixes.len() > 0);
@@ -283,7 +837,6 @@ mod lib_tests {
         let result = RNG::try_from(&Language::Goblin).unwrap();
 
         assert_eq!(result.name, Language::Goblin.to_string());
-        assert!(result.bad_syllables.len() < 1);
         assert!(result.prefixes.len() > 0);
         assert!(result.centers.len() > 0);
         assert!(result.suffixes.len() > 0);
@@ -294,7 +847,6 @@ mod lib_tests {
         let result = RNG::try_from(&Language::Roman).unwrap();
 
         assert_eq!(result.name, Language::Roman.to_string());
-        assert!(result.bad_syllables.len() < 1);
         assert!(result.prefixes.len() > 0);
         assert!(result.centers.len() > 0);
         assert!(result.suffixes.len() > 0);
@@ -305,7 +857,6 @@ mod lib_tests {
         let result = RNG::try_from(&Language::Klingon).unwrap();
 
         assert_eq!(result.name, Language::Klingon.to_string());
-        assert!(result.bad_syllables.len() < 1);
         assert!(result.prefixes.len() > 0);
         assert!(result.centers.len() > 0);
         assert!(result.suffixes.len() > 0);
@@ -316,7 +867,6 @@ mod lib_tests {
         let result = RNG::try_from(&Language::GermanCurse).unwrap();
 
         assert_eq!(result.name, Language::GermanCurse.to_string());
-        assert!(result.bad_syllables.len() < 1);
         assert!(result.prefixes.len() > 0);
         assert!(result.centers.len() > 0);
         assert!(result.suffixes.len() > 0);
@@ -327,12 +877,23 @@ mod lib_tests {
         let result = RNG::try_from(&Language::Фантазия).unwrap();
 
         assert_eq!(result.name, Language::Фантазия.to_string());
-        assert!(result.bad_syllables.len() < 1);
         assert!(result.prefixes.len() > 0);
         assert!(result.centers.len() > 0);
         assert!(result.suffixes.len() > 0);
     }
 
+    #[test]
+    fn for_locale() {
+        assert_eq!(
+            RNG::for_locale(&Language::Roman, "ru").unwrap(),
+            RNG::try_from(&Language::Римский).unwrap()
+        );
+        assert_eq!(
+            RNG::for_locale(&Language::Klingon, "ru").unwrap().name,
+            "Klingon"
+        );
+    }
+
     #[test]
     fn new_from_file() {
         let filename = "src/languages/Test-micro.txt";
@@ -342,7 +903,6 @@ mod lib_tests {
 
         assert!(!rng.is_err());
         assert_eq!(result.name, filename.to_string());
-        assert_eq!(result.bad_syllables.len(), 0);
         assert_eq!(result.prefixes.len(), 1);
         assert_eq!(result.centers.len(), 1);
         assert_eq!(result.suffixes.len(), 1);
@@ -357,7 +917,6 @@ mod lib_tests {
 
         assert!(!rng.is_err());
         assert_eq!(result.name, filename.to_string());
-        assert_eq!(result.bad_syllables.len(), 0);
         assert_eq!(result.prefixes.len(), 19);
         assert_eq!(result.centers.len(), 13);
         assert_eq!(result.suffixes.len(), 16);
@@ -372,6 +931,203 @@ mod lib_tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn new_from_file__read_error() {
+        let error = RNG::new_from_file("src/languages/none.txt".to_string()).unwrap_err();
+
+        assert_eq!(error.kind(), RNGErrorKind::ReadError);
+        assert_eq!(error.file(), Some("src/languages/none.txt"));
+        assert!(error.source().is_some());
+    }
+
+    #[test]
+    fn compile_file__invalid() {
+        let input = std::env::temp_dir().join(format!("rng-invalid-{}.txt", std::process::id()));
+        let input = input.to_string_lossy().to_string();
+        std::fs::write(&input, "-a\n!!\n+c").unwrap();
+
+        let error = RNG::compile_file(&input, "unused.rngc").unwrap_err();
+        std::fs::remove_file(&input).unwrap();
+
+        assert_eq!(error.kind(), RNGErrorKind::ParsingError);
+        assert_eq!(error.file(), Some(input.as_str()));
+        assert_eq!(error.line(), Some(2));
+        assert_eq!(error.text(), Some("!!"));
+    }
+
+    #[test]
+    fn new_from_str() {
+        let rng = RNG::new_from_str("Micro".to_string(), "-a\nb\n+c").unwrap();
+
+        assert_eq!(rng.name, "Micro");
+        assert_eq!(rng.prefixes.len(), 1);
+        assert_eq!(rng.centers.len(), 1);
+        assert_eq!(rng.suffixes.len(), 1);
+    }
+
+    #[test]
+    fn new_from_reader() {
+        let file = std::fs::File::open("src/languages/Гоблин.txt").unwrap();
+
+        let rng = RNG::new_from_reader("Goblin from the DB".to_string(), file).unwrap();
+
+        assert_eq!(rng.name, "Goblin from the DB");
+        assert_eq!(
+            rng,
+            RNG::new_from_str(
+                rng.name.clone(),
+                &std::fs::read_to_string("src/languages/Гоблин.txt").unwrap()
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn new_from_reader__compiled() {
+        let source = RNG::try_from(&Language::Roman).unwrap();
+        let bytes = source.compile().unwrap();
+
+        let rng = RNG::new_from_reader("Renamed".to_string(), bytes.as_slice()).unwrap();
+
+        assert_eq!(rng.name, "Renamed");
+        assert_eq!(rng.prefixes, source.prefixes);
+        assert_eq!(rng.centers, source.centers);
+        assert_eq!(rng.suffixes, source.suffixes);
+    }
+
+    #[test]
+    fn new_from_reader__invalid_utf8() {
+        let bytes: &[u8] = &[b'-', b'a', b'\n', b'b', b'\n', b'-', 0xff, 0xfe];
+
+        let error = RNG::new_from_reader("Broken".to_string(), bytes).unwrap_err();
+
+        assert_eq!(error.kind(), RNGErrorKind::InvalidLanguageFile);
+        assert_eq!(error.line(), Some(3));
+        assert!(error.source().is_some());
+    }
+
+    #[test]
+    fn new_from_buf_reader() {
+        let reader = std::io::Cursor::new("-a\n-b\nc\n+d\n+e");
+
+        let rng = RNG::new_from_buf_reader("Cursor".to_string(), reader).unwrap();
+
+        assert_eq!(rng.name, "Cursor");
+        assert_eq!(rng.prefixes.len(), 2);
+        assert_eq!(rng.centers.len(), 1);
+        assert_eq!(rng.suffixes.len(), 2);
+    }
+
+    #[test]
+    fn new_from_buf_reader__strict() {
+        let reader = std::io::Cursor::new("-a\n-b\nc\n+d\n+e\n!!");
+
+        let error = RNG::new_from_buf_reader("Cursor".to_string(), reader).unwrap_err();
+
+        assert_eq!(error.kind(), RNGErrorKind::ParsingError);
+        assert_eq!(error.line(), Some(6));
+        assert_eq!(error.text(), Some("!!"));
+    }
+
+    #[test]
+    fn load_reader__lenient() {
+        let reader = std::io::Cursor::new("-a\n-b\nc\n+d\n+e\n!!");
+
+        let (rng, report) =
+            RNG::load_reader("Cursor".to_string(), reader, LoadPolicy::Lenient).unwrap();
+
+        assert_eq!(rng.syllables().len(), 5);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].line, Some(6));
+        assert_eq!(report.skipped[0].text, "!!");
+    }
+
+    #[test]
+    fn load_file__lenient() {
+        let input = std::env::temp_dir().join(format!("rng-lenient-{}.txt", std::process::id()));
+        let input = input.to_string_lossy().to_string();
+        std::fs::write(&input, "-a\n-a\n!!\n+c").unwrap();
+
+        let strict = RNG::load_file(input.clone(), LoadPolicy::Strict);
+        let lenient = RNG::load_file(input.clone(), LoadPolicy::Lenient);
+        std::fs::remove_file(&input).unwrap();
+
+        let error = strict.unwrap_err();
+        assert_eq!(error.file(), Some(input.as_str()));
+        assert_eq!(error.line(), Some(3));
+        let (rng, report) = lenient.unwrap();
+        assert_eq!(rng.prefixes.len(), 2);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.duplicates.len(), 1);
+        assert_eq!(report.warnings.len(), 1);
+    }
+
+    #[test]
+    fn load__builtin_languages_are_clean_apart_from_duplicates() {
+        for language in [Language::Elven, Language::Goblin, Language::Fantasy] {
+            let asset = Asset::get(language.get_filename().as_str()).unwrap();
+            let text = std::str::from_utf8(asset.data.as_ref()).unwrap();
+
+            let (_, report) = RNG::load(language.to_string(), text, LoadPolicy::Lenient).unwrap();
+
+            assert!(report.skipped.is_empty());
+            assert!(report.warnings.is_empty());
+        }
+    }
+
+    #[test]
+    fn new_from_buf_reader__compiled() {
+        let source = RNG::try_from(&Language::Klingon).unwrap();
+        let bytes = source.compile().unwrap();
+
+        let rng = RNG::new_from_buf_reader("Klingon".to_string(), bytes.as_slice()).unwrap();
+
+        assert_eq!(rng, source);
+    }
+
+    #[test]
+    fn load_reader__a_byte_at_a_time() {
+        let source = RNG::try_from(&Language::Klingon).unwrap();
+        let bytes = source.compile().unwrap();
+        let text = source.to_language_file();
+
+        let (compiled, _) = RNG::load_reader(
+            "Klingon".to_string(),
+            std::io::BufReader::with_capacity(1, bytes.as_slice()),
+            LoadPolicy::Strict,
+        )
+        .unwrap();
+        let (parsed, _) = RNG::load_reader(
+            "Klingon".to_string(),
+            std::io::BufReader::with_capacity(1, text.as_bytes()),
+            LoadPolicy::Strict,
+        )
+        .unwrap();
+
+        assert_eq!(compiled, source);
+        assert_eq!(parsed, source);
+    }
+
+    #[test]
+    fn load_reader__shorter_than_the_header() {
+        let (rng, _) =
+            RNG::load_reader("Tiny".to_string(), "-a".as_bytes(), LoadPolicy::Lenient).unwrap();
+
+        assert_eq!(rng.prefixes.len(), 1);
+    }
+
+    #[test]
+    fn new_from_buf_reader__invalid_utf8() {
+        let bytes: &[u8] = &[b'-', b'a', b'\n', 0xff, 0xfe];
+
+        let result = RNG::new_from_buf_reader("Broken".to_string(), bytes);
+
+        assert_eq!(
+            result.unwrap_err().kind(),
+            RNGErrorKind::InvalidLanguageFile
+        );
+    }
+
     #[test]
     fn new_from_file__russian_fantasy() {
         let filename = "src/languages/Фантазия.txt";
@@ -381,12 +1137,102 @@ mod lib_tests {
 
         assert!(!rng.is_err());
         assert_eq!(result.name, filename.to_string());
-        assert_eq!(result.bad_syllables.len(), 0);
         assert_eq!(result.prefixes.len(), 180);
         assert_eq!(result.centers.len(), 157);
         assert_eq!(result.suffixes.len(), 19);
     }
 
+    #[test]
+    fn new_from_file__compiled() {
+        let source = RNG::try_from(&Language::Elven).unwrap();
+        let path = std::env::temp_dir().join(format!("rng-elven-{}.rngc", std::process::id()));
+        std::fs::write(&path, source.compile().unwrap()).unwrap();
+
+        let result = RNG::new_from_file(path.to_string_lossy().to_string());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(result.unwrap(), source);
+    }
+
+    #[test]
+    fn compile_file() {
+        let output = std::env::temp_dir().join(format!("rng-micro-{}.rngc", std::process::id()));
+        let output = output.to_string_lossy().to_string();
+
+        let compiled = RNG::compile_file("src/languages/Test-micro.txt", &output);
+        let loaded = RNG::new_from_file(output.clone());
+        std::fs::remove_file(&output).unwrap();
+
+        assert_eq!(compiled.unwrap(), loaded.unwrap());
+    }
+
+    #[test]
+    fn to_language_file() {
+        let rng = RNG::new_from_str("Micro".to_string(), "-ka +v *2\nri -c\n+zu").unwrap();
+
+        assert_eq!(rng.to_language_file(), "-ka +v *2\nri -c\n+zu\n");
+    }
+
+    #[test]
+    fn to_language_file__spellings() {
+        let file = "@spell latin ʃ sh\n@spell cyrillic ʃ ш\n-ʃa\nri\n+zu\n";
+        let rng = RNG::new_from_str("Micro".to_string(), file).unwrap();
+
+        assert_eq!(rng.to_language_file(), file);
+    }
+
+    #[test]
+    fn transliterated() {
+        let rng = RNG::new_from_str("Micro".to_string(), "-жа +c *3\nри\n+щё").unwrap();
+
+        assert_eq!(
+            rng.transliterated(Transliteration::Bgn).to_language_file(),
+            "-zha +c *3\nri\n+shchyo\n"
+        );
+        assert_eq!(
+            rng.transliterated(Transliteration::Iso9)
+                .generate_name_by_count(3),
+            "Žariŝë"
+        );
+    }
+
+    #[test]
+    fn to_language_file__capitalization() {
+        let file = "@capitalize preserve\n@spell latin ʃ sh\n-d'\nAr\n+c\n";
+        let rng = RNG::new_from_str("Micro".to_string(), file).unwrap();
+
+        assert_eq!(rng.to_language_file(), file);
+        assert_eq!(rng.generate_name_by_count(3), "d'Arc");
+        assert_eq!(rng.enumerate_names(3..=3).next().unwrap(), "d'Arc");
+    }
+
+    #[test]
+    fn in_script() {
+        let rng = RNG::new_from_str(
+            "Micro".to_string(),
+            "@spell latin ʃ sh\n@spell cyrillic ʃ ш\n@spell cyrillic a а\n-ʃa +c *3\nri\n+ʃ",
+        )
+        .unwrap();
+
+        let latin = rng.in_script(Script::Latin);
+        let cyrillic = rng.in_script(Script::Cyrillic);
+
+        assert_eq!(latin.to_language_file(), "-sha +c *3\nri\n+sh\n");
+        assert_eq!(cyrillic.to_language_file(), "-ша +c *3\nri\n+ш\n");
+        assert!(latin.orthography.is_empty());
+    }
+
+    #[test]
+    fn to_language_file__round_trips_built_in_languages() {
+        for language in Language::all() {
+            let rng = RNG::try_from(language).unwrap();
+
+            let reloaded = RNG::new_from_str(rng.name.clone(), &rng.to_language_file()).unwrap();
+
+            assert_eq!(reloaded, rng, "{language}");
+        }
+    }
+
     #[test]
     fn process_file__with_error() {
         let filename = "src/languages/none.txt";
@@ -401,10 +1247,10 @@ mod lib_tests {
         let raw = "-ваа +c\n-боо +c\n-гар\n-бар\n-дар\n-жар\n-вар\n-кра\n-гра\n-дра\n-зра\n-гоб\n-доб\n-роб\n-фоб\n-зоб\n-раг\n-наг\n-даг\nбра\nга\nда\nдо\nго\nзе\nша\nназ\nзуб\nзу\nна\nгор\nбу +c\n+быр\n+гыр\n+д";
         let filename = "src/languages/goblinRU.txt".to_string();
 
-        let classified = RNG::classify(raw, filename.clone());
+        let (classified, report) = RNG::load(filename.clone(), raw, LoadPolicy::Lenient).unwrap();
 
         assert_eq!(classified.name, filename);
-        assert_eq!(classified.bad_syllables.len(), 0);
+        assert!(report.skipped.is_empty());
         assert_eq!(classified.prefixes.len(), 19);
         assert_eq!(classified.centers.len(), 13);
         assert_eq!(classified.suffixes.len(), 3);
@@ -415,10 +1261,10 @@ mod lib_tests {
         let raw = "-а +c\n-аб\n-ак\n-ац\n-ад\n-аф\n-ам\n-ан\n-ап\n-ар\n-ас\n-ат\n-ав\n-аз\n-аэль\n-аэл\n-ао\n-аэр\n-аш\n-арш +v";
         let filename = "src/languages/goblinRU.txt".to_string();
 
-        let classified = RNG::classify(raw, filename.clone());
+        let (classified, report) = RNG::load(filename.clone(), raw, LoadPolicy::Lenient).unwrap();
 
         assert_eq!(classified.name, filename);
-        assert_eq!(classified.bad_syllables.len(), 0);
+        assert!(report.skipped.is_empty());
         // assert_eq!(classified.prefixes.len(), 19);
         // assert_eq!(classified.centers.len(), 13);
         // assert_eq!(classified.suffixes.len(), 3);
@@ -430,7 +1276,8 @@ mod lib_tests {
             prefixes: Syllables::new_from_array(&["a"]),
             centers: Syllables::new_from_array(&["b"]),
             suffixes: Syllables::new_from_array(&["c"]),
-            bad_syllables: vec![],
+            orthography: Orthography::new(),
+            capitalization: Capitalization::default(),
         }
     }
 
@@ -457,6 +1304,46 @@ mod lib_tests {
         assert_eq!(names.len(), 5);
     }
 
+    #[test]
+    fn generate_names_with__distinct() {
+        let rng = RNG::try_from(&Language::Roman).unwrap();
+        let diversity = Diversity::new().within(2).phonetic();
+        let constraints = NameConstraints::new().with_diversity(diversity.clone());
+
+        let names = rng.generate_names_with(10, &constraints).unwrap();
+
+        assert_eq!(names.len(), 10);
+        for (i, name) in names.iter().enumerate() {
+            assert!(diversity.is_distinct(name, &names[..i]), "{names:?}");
+        }
+    }
+
+    #[test]
+    fn pseudonym() {
+        let rng = RNG::try_from(&Language::Goblin).unwrap();
+
+        assert_eq!(
+            rng.pseudonym(b"s3cret", "alice"),
+            rng.pseudonym(b"s3cret", "alice")
+        );
+        assert_ne!(
+            rng.pseudonym(b"other", "alice"),
+            rng.pseudonym(b"s3cret", "alice")
+        );
+    }
+
+    #[test]
+    fn pseudonym__is_a_seeded_name() {
+        let rng = RNG::try_from(&Language::Roman).unwrap();
+        let mut seeded =
+            rand::rngs::Xoshiro256PlusPlus::from_seed(rng_pseudonym::seed(b"s3cret", "alice"));
+
+        assert_eq!(
+            rng.pseudonym(b"s3cret", "alice"),
+            rng.generate_name_with_rng(&mut seeded)
+        );
+    }
+
     #[test]
     fn generate_names_string() {
         let rng = RNG::try_from(&Language::Demonic).unwrap();
@@ -530,7 +1417,8 @@ mod lib_tests {
             prefixes: Syllables::new(),
             centers: Syllables::new(),
             suffixes: Syllables::new(),
-            bad_syllables: vec!["#$@!".to_string()],
+            orthography: Orthography::new(),
+            capitalization: Capitalization::default(),
         };
         assert!(!bad.is_valid())
     }
@@ -658,7 +1546,10 @@ mod lib_tests {
 
 // region Language
 
-#[derive(Clone, Debug, PartialEq)]
+/// The locale languages fall back to when no variant matches the one requested.
+pub const DEFAULT_LOCALE: &str = "en";
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum Language {
     Curse,
     Demonic,
@@ -685,19 +1576,151 @@ impl fmt::Display for Language {
     }
 }
 
+/// Picks any of the production-ready language families with equal probability, in its
+/// `DEFAULT_LOCALE` variant, so `Goblin` is as likely as `Klingon` rather than twice as likely
+/// for having a Russian variant as well. Use `Language::for_locale` on the result for another
+/// locale, or a `LanguageSelector` to choose from other languages, or with other weights.
 impl Distribution<Language> for StandardUniform {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Language {
-        match rng.random_range(0..5) {
-            0 => Language::Demonic,
-            1 => Language::Elven,
-            2 => Language::Fantasy,
-            3 => Language::Goblin,
-            _ => Language::Roman,
-        }
+        let ready = Language::one_per_family();
+        ready[rng.random_range(0..ready.len())]
+    }
+}
+
+impl FromStr for Language {
+    type Err = BadLanguage;
+
+    /// Parses a language by name, ignoring case, hyphens, underscores and spaces, so `elven`,
+    /// `German-curse`, `german_curse` and `ЭЛЬФИЙСКИЙ` are all accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Language::all()
+            .iter()
+            .find(|language| same_name(&language.to_string(), s))
+            .copied()
+            .ok_or(BadLanguage)
     }
 }
 
 impl Language {
+    /// Every built-in language, in declaration order.
+    #[must_use]
+    pub fn all() -> &'static [Language] {
+        &[
+            Language::Curse,
+            Language::Demonic,
+            Language::Elven,
+            Language::Эльфийский,
+            Language::Fantasy,
+            Language::Фантазия,
+            Language::GermanCurse,
+            Language::Goblin,
+            Language::Гоблин,
+            Language::Klingon,
+            Language::Roman,
+            Language::Римский,
+        ]
+    }
+
+    /// Describes the language: the script its syllables are written in, its locale, and whether
+    /// it is ready for use.
+    #[must_use]
+    pub fn info(&self) -> LanguageInfo {
+        let (script, locale) = match self {
+            Language::Эльфийский | Language::Фантазия | Language::Гоблин | Language::Римский => {
+                (Script::Cyrillic, "ru")
+            }
+            Language::GermanCurse => (Script::Latin, "de"),
+            _ => (Script::Latin, "en"),
+        };
+        LanguageInfo {
+            language: *self,
+            script,
+            locale,
+            production_ready: !matches!(self, Language::Curse | Language::GermanCurse),
+        }
+    }
+
+    /// The language and its variants in other locales, such as `Elven` and `Эльфийский`.
+    #[must_use]
+    pub fn family(&self) -> &'static [Language] {
+        match self {
+            Language::Elven | Language::Эльфийский => {
+                &[Language::Elven, Language::Эльфийский]
+            }
+            Language::Fantasy | Language::Фантазия => {
+                &[Language::Fantasy, Language::Фантазия]
+            }
+            Language::Goblin | Language::Гоблин => &[Language::Goblin, Language::Гоблин],
+            Language::Roman | Language::Римский => &[Language::Roman, Language::Римский],
+            Language::Curse => &[Language::Curse],
+            Language::Demonic => &[Language::Demonic],
+            Language::GermanCurse => &[Language::GermanCurse],
+            Language::Klingon => &[Language::Klingon],
+        }
+    }
+
+    /// The production-ready languages, one variant of each family, in `DEFAULT_LOCALE` where
+    /// there is a choice.
+    pub(crate) fn one_per_family() -> Vec<Language> {
+        Language::all()
+            .iter()
+            .filter(|language| {
+                language.info().production_ready
+                    && language.for_locale(DEFAULT_LOCALE) == **language
+            })
+            .copied()
+            .collect()
+    }
+
+    /// The variant of this language for a locale, if there is one. Only the primary language
+    /// subtag is considered, so `ru`, `RU`, `ru-RU` and `ru_RU` are equivalent.
+    #[must_use]
+    pub fn with_locale(&self, locale: &str) -> Option<Language> {
+        let primary = locale
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        self.family()
+            .iter()
+            .find(|language| language.info().locale == primary)
+            .copied()
+    }
+
+    /// Resolves the variant of this language for a locale, falling back to `DEFAULT_LOCALE`, and
+    /// then to the language itself when it has no variant in either.
+    ///
+    /// ```
+    /// use rnglib::Language;
+    ///
+    /// assert_eq!(Language::Goblin.for_locale("ru"), Language::Гоблин);
+    /// assert_eq!(Language::Гоблин.for_locale("fr"), Language::Goblin);
+    /// assert_eq!(Language::Demonic.for_locale("ru"), Language::Demonic);
+    /// ```
+    #[must_use]
+    pub fn for_locale(&self, locale: &str) -> Language {
+        self.for_locales(&[locale])
+    }
+
+    /// Resolves the variant of this language for the first of the locales, in order of
+    /// preference, that it has one for, such as a user's `["uk", "ru", "en"]`. Falls back to
+    /// `DEFAULT_LOCALE`, and then to the language itself, like `for_locale`.
+    ///
+    /// ```
+    /// use rnglib::Language;
+    ///
+    /// assert_eq!(Language::Goblin.for_locales(&["uk", "ru"]), Language::Гоблин);
+    /// assert_eq!(Language::Гоблин.for_locales(&["uk", "en-GB", "ru"]), Language::Goblin);
+    /// ```
+    #[must_use]
+    pub fn for_locales(&self, locales: &[&str]) -> Language {
+        locales
+            .iter()
+            .find_map(|locale| self.with_locale(locale))
+            .or_else(|| self.with_locale(DEFAULT_LOCALE))
+            .unwrap_or(*self)
+    }
+
     #[must_use]
     pub fn get_filename(&self) -> String {
         format!("{self}.txt")
@@ -709,7 +1732,43 @@ impl Language {
     }
 }
 
-/// This may come in handy some day.
+/// The writing system a language's syllables are in.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Script {
+    Latin,
+    Cyrillic,
+}
+
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl FromStr for Script {
+    type Err = BadScript;
+
+    /// Parses a script by name, ignoring case, so `latin` and `Cyrillic` are both accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [Script::Latin, Script::Cyrillic]
+            .into_iter()
+            .find(|script| script.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or(BadScript)
+    }
+}
+
+/// Metadata about a built-in `Language`, see `Language::info`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LanguageInfo {
+    pub language: Language,
+    pub script: Script,
+    /// ISO 639-1 code of the language the syllables are drawn from.
+    pub locale: &'static str,
+    /// `false` for languages that are still under construction.
+    pub production_ready: bool,
+}
+
+/// Returned when a string doesn't name a `Language`.
 #[derive(Debug, Clone, PartialEq)]
 pub struct BadLanguage;
 
@@ -719,10 +1778,25 @@ impl fmt::Display for BadLanguage {
     }
 }
 
+impl std::error::Error for BadLanguage {}
+
+/// Returned when a string doesn't name a `Script`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BadScript;
+
+impl fmt::Display for BadScript {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid Script")
+    }
+}
+
+impl std::error::Error for BadScript {}
+
 #[cfg(test)]
 #[allow(non_snake_case)]
 mod test_language {
     use super::*;
+    use rstest::rstest;
 
     #[test]
     fn to_filename() {
@@ -749,5 +1823,133 @@ mod test_language {
             Language::Fantasy.get_path()
         );
     }
+
+    #[rstest]
+    #[case("Elven", Language::Elven)]
+    #[case("elven", Language::Elven)]
+    #[case("FANTASY", Language::Fantasy)]
+    #[case("German-curse", Language::GermanCurse)]
+    #[case("german_curse", Language::GermanCurse)]
+    #[case("GermanCurse", Language::GermanCurse)]
+    #[case("Эльфийский", Language::Эльфийский)]
+    #[case("гоблин", Language::Гоблин)]
+    #[case("РИМСКИЙ", Language::Римский)]
+    fn from_str(#[case] input: &str, #[case] expected: Language) {
+        assert_eq!(Language::from_str(input), Ok(expected));
+    }
+
+    #[test]
+    fn from_str__invalid() {
+        assert_eq!(Language::from_str("Vulcan"), Err(BadLanguage));
+        assert_eq!(Language::from_str(""), Err(BadLanguage));
+    }
+
+    #[rstest]
+    #[case("Latin", Script::Latin)]
+    #[case("latin", Script::Latin)]
+    #[case("CYRILLIC", Script::Cyrillic)]
+    fn script__from_str(#[case] input: &str, #[case] expected: Script) {
+        assert_eq!(Script::from_str(input), Ok(expected));
+        assert_eq!(Script::from_str("Greek"), Err(BadScript));
+    }
+
+    #[test]
+    fn from_str__round_trips_display() {
+        for language in Language::all() {
+            assert_eq!(Language::from_str(&language.to_string()), Ok(*language));
+        }
+    }
+
+    #[test]
+    fn all() {
+        assert_eq!(Language::all().len(), 12);
+        for language in Language::all() {
+            assert!(Asset::get(&language.get_filename()).is_some(), "{language}");
+        }
+    }
+
+    #[test]
+    fn info() {
+        let info = Language::Гоблин.info();
+
+        assert_eq!(info.language, Language::Гоблин);
+        assert_eq!(info.script, Script::Cyrillic);
+        assert_eq!(info.locale, "ru");
+        assert!(info.production_ready);
+    }
+
+    #[test]
+    fn sample() {
+        let languages: Vec<Language> = (0..500).map(|_| rand::random()).collect();
+
+        assert!(languages.contains(&Language::Klingon));
+        assert!(languages.contains(&Language::Roman));
+        assert!(!languages.contains(&Language::Римский));
+        assert!(languages.iter().all(|language| {
+            language.info().production_ready && language.info().locale == DEFAULT_LOCALE
+        }));
+    }
+
+    #[test]
+    fn family() {
+        assert_eq!(
+            Language::Римский.family(),
+            &[Language::Roman, Language::Римский]
+        );
+        assert_eq!(Language::Klingon.family(), &[Language::Klingon]);
+        for language in Language::all() {
+            assert!(language.family().contains(language));
+        }
+    }
+
+    #[rstest]
+    #[case(Language::Elven, "ru", Some(Language::Эльфийский))]
+    #[case(Language::Elven, "ru-RU", Some(Language::Эльфийский))]
+    #[case(Language::Elven, "RU_ru", Some(Language::Эльфийский))]
+    #[case(Language::Эльфийский, "en", Some(Language::Elven))]
+    #[case(Language::Elven, "en-GB", Some(Language::Elven))]
+    #[case(Language::Elven, "fr", None)]
+    #[case(Language::Demonic, "ru", None)]
+    #[case(Language::GermanCurse, "de", Some(Language::GermanCurse))]
+    fn with_locale(
+        #[case] language: Language,
+        #[case] locale: &str,
+        #[case] expected: Option<Language>,
+    ) {
+        assert_eq!(language.with_locale(locale), expected);
+    }
+
+    #[rstest]
+    #[case(Language::Fantasy, "ru", Language::Фантазия)]
+    #[case(Language::Фантазия, "ru", Language::Фантазия)]
+    #[case(Language::Фантазия, "de", Language::Fantasy)]
+    #[case(Language::Klingon, "ru", Language::Klingon)]
+    #[case(Language::GermanCurse, "en", Language::GermanCurse)]
+    #[case(Language::Roman, "", Language::Roman)]
+    fn for_locale(#[case] language: Language, #[case] locale: &str, #[case] expected: Language) {
+        assert_eq!(language.for_locale(locale), expected);
+    }
+
+    #[rstest]
+    #[case(Language::Goblin, &["uk", "ru"], Language::Гоблин)]
+    #[case(Language::Гоблин, &["de", "en-US", "ru"], Language::Goblin)]
+    #[case(Language::Elven, &["ru_RU", "en"], Language::Эльфийский)]
+    #[case(Language::Эльфийский, &["fr", "uk"], Language::Elven)]
+    #[case(Language::GermanCurse, &["ru", "fr"], Language::GermanCurse)]
+    #[case(Language::Roman, &[], Language::Roman)]
+    fn for_locales(
+        #[case] language: Language,
+        #[case] locales: &[&str],
+        #[case] expected: Language,
+    ) {
+        assert_eq!(language.for_locales(locales), expected);
+    }
+
+    #[test]
+    fn info__under_construction() {
+        assert!(!Language::Curse.info().production_ready);
+        assert!(!Language::GermanCurse.info().production_ready);
+        assert!(Language::Klingon.info().production_ready);
+    }
 }
 // endregion
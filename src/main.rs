@@ -1,31 +1,101 @@
-use clap::{Arg, ArgAction, ArgMatches, command};
-use rnglib::{Language, RNG, RNGError};
+use clap::{Arg, ArgAction, ArgGroup, ArgMatches, Command, command};
+use regex::Regex;
+use rnglib::{
+    Blender, BlockMode, Blocklist, COMPILED_EXTENSION, Diversity, LANGUAGES_PATH_ENV, Language,
+    LanguageRegistry, LanguageSelector, LanguageSource, LoadPolicy, NameCase, NameConstraints,
+    Phonology, RNG, RNGError, RNGErrorKind, Script, Transliteration,
+};
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::str::FromStr;
 
 static HELP_TEMPLATE: &str = "{about} {version}
 
-Usage: rng
+Usage: rng [OPTIONS] [COMMAND]
+
+Commands:
+{subcommands}
 
 Options:
 {options}
 ";
 
-fn main() -> Result<(), RNGError> {
+/// Names longer than this aren't identified, since scoring one takes time in proportion to its
+/// length times the number of syllables in every language.
+const MAX_IDENTIFY_LENGTH: usize = 64;
+
+/// Environment variable `rng anonymize` reads the secret key from when there is no `--key-file`,
+/// so the key doesn't show up in `ps` or the shell history.
+const KEY_ENV: &str = "RNG_ANONYMIZE_KEY";
+
+fn main() -> anyhow::Result<()> {
     let matches = cmd().get_matches();
 
-    let count: usize = *get_number(&matches).ok_or(RNGError::ParsingError)?;
-    let rng = get_rng(&matches)?;
+    if let Some(("compile", sub)) = matches.subcommand() {
+        compile(sub)?;
+        return Ok(());
+    }
+    if let Some(("blend", sub)) = matches.subcommand() {
+        blend(sub)?;
+        return Ok(());
+    }
+    if let Some(("conlang", sub)) = matches.subcommand() {
+        conlang(sub)?;
+        return Ok(());
+    }
+    if let Some(("train", sub)) = matches.subcommand() {
+        train(sub)?;
+        return Ok(());
+    }
+    if let Some(("anonymize", sub)) = matches.subcommand() {
+        anonymize(sub)?;
+        return Ok(());
+    }
+    if let Some(("identify", sub)) = matches.subcommand() {
+        identify(sub);
+        return Ok(());
+    }
+    if let Some(("list", _)) = matches.subcommand() {
+        list();
+        return Ok(());
+    }
+
+    let count: usize = *get_number(&matches).ok_or(RNGError::new(RNGErrorKind::ParsingError))?;
+    let mut rng = match matches.get_one::<Script>("script") {
+        Some(script) => get_rng(&matches)?.in_script(*script),
+        None => get_rng(&matches)?,
+    };
+    if let Some(standard) = matches.get_one::<Transliteration>("translit") {
+        rng = rng.transliterated(*standard);
+    }
+    let names = match get_constraints(&matches)? {
+        Some(constraints) => {
+            let names = rng.generate_names_with(count, &constraints)?;
+            if let Some(rejected) = constraints
+                .blocklist()
+                .map(Blocklist::rejected)
+                .filter(|rejected| *rejected > 0)
+            {
+                eprintln!("{}: {rejected} names rejected by the blocklist", rng.name);
+            }
+            names
+        }
+        None if matches.get_flag("stratified") => {
+            rng.generate_names_stratified(count, matches.get_flag("short"))
+        }
+        None => rng.generate_names(count, matches.get_flag("short")),
+    };
+
+    let names = match matches.get_one::<NameCase>("case") {
+        Some(case) => names.iter().map(|name| case.apply(name)).collect(),
+        None => names,
+    }
+    .join(" ");
 
     if matches.get_flag("no-prefix") {
-        println!(
-            "{}",
-            rng.generate_names_string(count, matches.get_flag("short"))
-        );
+        println!("{names}");
     } else {
-        println!(
-            "{}: {}",
-            rng.name,
-            rng.generate_names_string(count, matches.get_flag("short"))
-        );
+        println!("{}: {names}", rng.name);
     }
 
     Ok(())
@@ -100,12 +170,66 @@ fn cmd() -> clap::Command {
                 .action(ArgAction::SetTrue)
                 .help("Flipmode is the greatest! (Random language)"),
         )
+        .arg(
+            Arg::new("include")
+                .long("include")
+                .required(false)
+                .value_name("NAME[:WEIGHT]")
+                .action(ArgAction::Append)
+                .value_parser(parse_include)
+                .requires("flipmode")
+                .help("Language, or language file, for flipmode to choose from instead of the defaults"),
+        )
+        .arg(
+            Arg::new("exclude")
+                .long("exclude")
+                .required(false)
+                .value_name("NAME")
+                .action(ArgAction::Append)
+                .requires("flipmode")
+                .help("Language for flipmode never to choose"),
+        )
         .arg(
             Arg::new("russian")
                 .long("russian")
                 .required(false)
                 .action(ArgAction::SetTrue)
-                .help("Use Russian language file, if available"),
+                .conflicts_with("locale")
+                .help("Use Russian language file, if available (same as --locale ru)"),
+        )
+        .arg(
+            Arg::new("locale")
+                .long("locale")
+                .required(false)
+                .value_name("LOCALE")
+                .help("Use the language's variant for a locale such as ru, if available"),
+        )
+        .arg(
+            Arg::new("script")
+                .long("script")
+                .required(false)
+                .value_name("SCRIPT")
+                .value_parser(Script::from_str)
+                .help("Spells a language written in phonemes in latin or cyrillic"),
+        )
+        .arg(
+            Arg::new("translit")
+                .long("translit")
+                .required(false)
+                .value_name("STANDARD")
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("bgn")
+                .value_parser(Transliteration::from_str)
+                .help("Writes Cyrillic names in Latin letters, a syllable at a time: bgn (default), gost or iso9"),
+        )
+        .arg(
+            Arg::new("case")
+                .long("case")
+                .required(false)
+                .value_name("CASE")
+                .value_parser(NameCase::from_str)
+                .help("Writes each name as an ASCII slug, snake_case, kebab-case, CamelCase or SCREAMING_CASE"),
         )
         .arg(
             Arg::new("short")
@@ -114,12 +238,144 @@ fn cmd() -> clap::Command {
                 .action(ArgAction::SetTrue)
                 .help("Creates shorter names"),
         )
+        .arg(
+            Arg::new("stratified")
+                .long("stratified")
+                .required(false)
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all([
+                    "min-length",
+                    "max-length",
+                    "starts-with",
+                    "contains",
+                    "excludes",
+                    "matches",
+                    "blocklist",
+                    "distinct",
+                    "phonetic",
+                    "existing",
+                ])
+                .help("Spreads the names out over the language's prefixes and suffixes"),
+        )
+        .arg(
+            Arg::new("min-length")
+                .long("min-length")
+                .required(false)
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .conflicts_with("short")
+                .help("Creates names at least N letters long"),
+        )
+        .arg(
+            Arg::new("max-length")
+                .long("max-length")
+                .required(false)
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .conflicts_with("short")
+                .help("Creates names at most N letters long"),
+        )
+        .arg(
+            Arg::new("starts-with")
+                .long("starts-with")
+                .required(false)
+                .value_name("LETTER")
+                .value_parser(clap::value_parser!(char))
+                .conflicts_with("short")
+                .help("Creates names starting with LETTER"),
+        )
+        .arg(
+            Arg::new("contains")
+                .long("contains")
+                .required(false)
+                .value_name("TEXT")
+                .action(ArgAction::Append)
+                .conflicts_with("short")
+                .help("Creates names containing TEXT"),
+        )
+        .arg(
+            Arg::new("excludes")
+                .long("excludes")
+                .required(false)
+                .value_name("TEXT")
+                .action(ArgAction::Append)
+                .conflicts_with("short")
+                .help("Creates names that don't contain TEXT"),
+        )
+        .arg(
+            Arg::new("matches")
+                .long("matches")
+                .required(false)
+                .value_name("REGEX")
+                .value_parser(|s: &str| Regex::new(s))
+                .conflicts_with("short")
+                .help("Creates names matching the regular expression REGEX"),
+        )
+        .arg(
+            Arg::new("blocklist")
+                .long("blocklist")
+                .required(false)
+                .value_name("FILE")
+                .action(ArgAction::Append)
+                .conflicts_with("short")
+                .help("Rejects names matching a word in the word list FILE"),
+        )
+        .arg(
+            Arg::new("block-mode")
+                .long("block-mode")
+                .required(false)
+                .value_name("MODE")
+                .value_parser(["exact", "substring", "normalized"])
+                .default_value("normalized")
+                .help("How --blocklist words are matched"),
+        )
+        .arg(
+            Arg::new("distinct")
+                .long("distinct")
+                .required(false)
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .conflicts_with("short")
+                .help("Rejects names within N edits of another name in the batch"),
+        )
+        .arg(
+            Arg::new("phonetic")
+                .long("phonetic")
+                .required(false)
+                .action(ArgAction::SetTrue)
+                .conflicts_with("short")
+                .help("Rejects names that sound like another name in the batch"),
+        )
+        .arg(
+            Arg::new("existing")
+                .long("existing")
+                .required(false)
+                .value_name("FILE")
+                .action(ArgAction::Append)
+                .conflicts_with("short")
+                .help("Rejects names too like those in FILE, one per line, as well"),
+        )
+        .arg(
+            Arg::new("language")
+                .short('l')
+                .long("language")
+                .required(false)
+                .value_name("NAME")
+                .help("Uses a language by name, including your own (see `rng list`)"),
+        )
         .arg(
             Arg::new("raw")
                 .long("raw")
                 .required(false)
                 .value_name("FILE")
-                .help("Reads in a raw language file"),
+                .help("Reads in a raw language file ('-' reads from stdin)"),
+        )
+        .arg(
+            Arg::new("lenient")
+                .long("lenient")
+                .required(false)
+                .action(ArgAction::SetTrue)
+                .help("Skip invalid lines in a raw language file, reporting them on stderr"),
         )
         .arg(
             Arg::new("no-prefix")
@@ -138,9 +394,559 @@ fn cmd() -> clap::Command {
                 .value_parser(clap::value_parser!(usize))
                 .help("Number of names created."),
         )
+        .subcommand(
+            Command::new("compile")
+                .about("Compiles a language file into the binary format for fast loading")
+                .arg(
+                    Arg::new("input")
+                        .required(true)
+                        .value_name("FILE")
+                        .help("Text language file to compile"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .required(false)
+                        .value_name("FILE")
+                        .help(
+                            "Where to write the compiled file [default: FILE with .rngc extension]",
+                        ),
+                ),
+        )
+        .subcommand(
+            Command::new("blend")
+                .about("Blends several languages into a hybrid one")
+                .arg(
+                    Arg::new("languages")
+                        .required(true)
+                        .num_args(1..)
+                        .value_name("NAME[:WEIGHT]")
+                        .value_parser(parse_include)
+                        .help("Languages, or language files, to blend"),
+                )
+                .arg(
+                    Arg::new("name")
+                        .long("name")
+                        .required(false)
+                        .value_name("NAME")
+                        .help("Name of the blended language [default: the languages' names joined with '-']"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .required(false)
+                        .value_name("FILE")
+                        .help("Writes the blended language file instead of generating names"),
+                )
+                .arg(
+                    Arg::new("number")
+                        .short('n')
+                        .long("number")
+                        .required(false)
+                        .default_value("2")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("Number of names created."),
+                ),
+        )
+        .subcommand(
+            Command::new("train")
+                .about("Builds a language from a corpus of example words")
+                .arg(
+                    Arg::new("corpus")
+                        .required(true)
+                        .value_name("FILE")
+                        .help("Example words, such as a list of names ('-' reads from stdin)"),
+                )
+                .arg(
+                    Arg::new("name")
+                        .long("name")
+                        .required(false)
+                        .value_name("NAME")
+                        .help("Name of the language [default: the corpus file's name]"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .required(false)
+                        .value_name("FILE")
+                        .help("Writes the language file instead of generating names"),
+                )
+                .arg(
+                    Arg::new("number")
+                        .short('n')
+                        .long("number")
+                        .required(false)
+                        .default_value("2")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("Number of names created."),
+                ),
+        )
+        .subcommand(
+            Command::new("conlang")
+                .about("Builds a new language from a sound system")
+                .arg(
+                    Arg::new("consonants")
+                        .long("consonants")
+                        .required(true)
+                        .value_name("SOUNDS")
+                        .help("Consonants, separated by spaces or commas, such as \"p t k sh\""),
+                )
+                .arg(
+                    Arg::new("vowels")
+                        .long("vowels")
+                        .required(true)
+                        .value_name("SOUNDS")
+                        .help("Vowels, separated by spaces or commas"),
+                )
+                .arg(
+                    Arg::new("shapes")
+                        .long("shapes")
+                        .required(false)
+                        .value_name("SHAPES")
+                        .default_value("CV CVC")
+                        .help("Syllable shapes, with C for a consonant and V for a vowel"),
+                )
+                .arg(
+                    Arg::new("ban")
+                        .long("ban")
+                        .required(false)
+                        .action(ArgAction::Append)
+                        .value_name("CLUSTER")
+                        .help("Keeps a cluster of sounds out of the syllables"),
+                )
+                .arg(
+                    Arg::new("seed")
+                        .long("seed")
+                        .required(false)
+                        .value_name("SEED")
+                        .value_parser(clap::value_parser!(u64))
+                        .help("Seed for the language [default: random]"),
+                )
+                .arg(
+                    Arg::new("syllables")
+                        .long("syllables")
+                        .required(false)
+                        .value_name("N")
+                        .default_value("24")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("Number of syllables to aim for in each of the prefixes, centers and suffixes"),
+                )
+                .arg(
+                    Arg::new("name")
+                        .long("name")
+                        .required(false)
+                        .value_name("NAME")
+                        .default_value("Conlang")
+                        .help("Name of the language"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .required(false)
+                        .value_name("FILE")
+                        .help("Writes the language file instead of generating names"),
+                )
+                .arg(
+                    Arg::new("number")
+                        .short('n')
+                        .long("number")
+                        .required(false)
+                        .default_value("2")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("Number of names created."),
+                ),
+        )
+        .subcommand(
+            Command::new("anonymize")
+                .about("Replaces names in a CSV column, or in log lines, with stable pseudonyms")
+                .arg(
+                    Arg::new("input")
+                        .required(false)
+                        .value_name("FILE")
+                        .default_value("-")
+                        .help("File to anonymize ('-' reads from stdin)"),
+                )
+                .arg(
+                    Arg::new("language")
+                        .short('l')
+                        .long("language")
+                        .required(true)
+                        .value_name("NAME")
+                        .help("Language to make the pseudonyms in"),
+                )
+                .arg(
+                    Arg::new("key-file")
+                        .long("key-file")
+                        .value_name("FILE")
+                        .help(
+                            "Reads the secret key from FILE, or else from $RNG_ANONYMIZE_KEY; \
+                             the same key always gives the same pseudonyms",
+                        ),
+                )
+                .arg(
+                    Arg::new("column")
+                        .long("column")
+                        .value_name("COLUMN")
+                        .help("CSV column to anonymize, by header name or number, starting at 1"),
+                )
+                .arg(
+                    Arg::new("matches")
+                        .long("matches")
+                        .value_name("REGEX")
+                        .value_parser(|s: &str| Regex::new(s))
+                        .help("Anonymizes each match in each line, or just its first group"),
+                )
+                .group(
+                    ArgGroup::new("target")
+                        .args(["column", "matches"])
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("delimiter")
+                        .long("delimiter")
+                        .value_name("CHAR")
+                        .default_value(",")
+                        .value_parser(clap::value_parser!(char))
+                        .help("CSV field delimiter"),
+                )
+                .arg(
+                    Arg::new("no-header")
+                        .long("no-header")
+                        .action(ArgAction::SetTrue)
+                        .requires("column")
+                        .help("The CSV has no header row"),
+                ),
+        )
+        .subcommand(
+            Command::new("identify")
+                .about("Shows which languages most likely made a name")
+                .arg(
+                    Arg::new("names")
+                        .required(true)
+                        .num_args(1..)
+                        .value_name("NAME")
+                        .help("Names to identify, of up to 64 characters"),
+                ),
+        )
+        .subcommand(
+            Command::new("list")
+                .about("Lists the available languages and where each one comes from"),
+        )
         .arg_required_else_help(true)
 }
 
+fn compile(matches: &ArgMatches) -> Result<(), RNGError> {
+    let input = matches
+        .get_one::<String>("input")
+        .ok_or(RNGErrorKind::ParsingError)?;
+    let output = match matches.get_one::<String>("output") {
+        Some(output) => output.clone(),
+        None => Path::new(input)
+            .with_extension(COMPILED_EXTENSION)
+            .to_string_lossy()
+            .to_string(),
+    };
+
+    let rng = RNG::compile_file(input, output.as_str())?;
+    println!("{}: compiled to {output}", rng.name);
+
+    Ok(())
+}
+
+fn blend(matches: &ArgMatches) -> Result<(), RNGError> {
+    let registry = LanguageRegistry::from_env();
+    let mut names: Vec<String> = Vec::new();
+    let mut parts: Vec<(RNG, u32)> = Vec::new();
+    for (name, weight) in matches
+        .get_many::<(String, u32)>("languages")
+        .unwrap_or_default()
+    {
+        let rng = match registry.find(name) {
+            Some(entry) => entry.load_with(&registry)?,
+            None => RNG::new_from_file(name.clone())?,
+        };
+        names.push(rng.name.clone());
+        parts.push((rng, *weight));
+    }
+
+    let name = matches
+        .get_one::<String>("name")
+        .cloned()
+        .unwrap_or_else(|| names.join("-"));
+    let rng = parts
+        .iter()
+        .fold(Blender::new(&name), |blender, (rng, weight)| {
+            blender.with(rng, *weight)
+        })
+        .blend()?;
+
+    write_or_print(matches, &rng, |output| format!("blended to {output}"))
+}
+
+/// Writes the language to the `--output` file if there is one, printing the message for it, and
+/// otherwise prints `--number` names from it.
+fn write_or_print(
+    matches: &ArgMatches,
+    rng: &RNG,
+    message: impl FnOnce(&str) -> String,
+) -> Result<(), RNGError> {
+    if let Some(output) = matches.get_one::<String>("output") {
+        std::fs::write(output, rng.to_language_file()).map_err(|e| {
+            RNGError::new(RNGErrorKind::WriteError)
+                .with_file(output)
+                .with_source(e)
+        })?;
+        println!("{}: {}", rng.name, message(output));
+    } else {
+        let count = matches.get_one::<usize>("number").copied().unwrap_or(2);
+        println!("{}: {}", rng.name, rng.generate_names_string(count, false));
+    }
+    Ok(())
+}
+
+fn conlang(matches: &ArgMatches) -> Result<(), RNGError> {
+    let text = |id: &str| matches.get_one::<String>(id).map_or("", String::as_str);
+    let mut phonology = Phonology::new(text("name"))
+        .with_consonants(text("consonants"))
+        .with_vowels(text("vowels"))
+        .with_shapes(text("shapes"));
+    for cluster in matches.get_many::<String>("ban").unwrap_or_default() {
+        phonology = phonology.banning(cluster);
+    }
+    if let Some(seed) = matches.get_one::<u64>("seed") {
+        phonology = phonology.with_seed(*seed);
+    }
+    if let Some(syllables) = matches.get_one::<usize>("syllables") {
+        phonology = phonology.with_syllables(*syllables);
+    }
+    let rng = phonology.generate()?;
+
+    write_or_print(matches, &rng, |output| {
+        format!("generated to {output} with seed {}", phonology.seed())
+    })
+}
+
+fn train(matches: &ArgMatches) -> Result<(), RNGError> {
+    let corpus_path = matches
+        .get_one::<String>("corpus")
+        .map_or("-", String::as_str);
+    let corpus = if corpus_path == "-" {
+        std::io::read_to_string(std::io::stdin())?
+    } else {
+        std::fs::read_to_string(corpus_path)
+            .map_err(|e| RNGError::from(e).with_file(corpus_path))?
+    };
+
+    let name = matches
+        .get_one::<String>("name")
+        .cloned()
+        .unwrap_or_else(|| {
+            Path::new(corpus_path)
+                .file_stem()
+                .filter(|_| corpus_path != "-")
+                .map_or_else(|| "stdin".to_string(), |s| s.to_string_lossy().to_string())
+        });
+    let rng = RNG::train(name, &corpus)?;
+
+    write_or_print(matches, &rng, |output| format!("trained to {output}"))
+}
+
+fn anonymize(matches: &ArgMatches) -> anyhow::Result<()> {
+    let language = matches
+        .get_one::<String>("language")
+        .ok_or(RNGError::from(RNGErrorKind::UnknownLanguage))?;
+    let rng = LanguageRegistry::from_env().load(language)?;
+    let key = if let Some(file) = matches.get_one::<String>("key-file") {
+        let mut key =
+            std::fs::read(file).map_err(|e| RNGError::from(e).with_file(file.as_str()))?;
+        while key.last().is_some_and(|b| *b == b'\n' || *b == b'\r') {
+            key.pop();
+        }
+        key
+    } else if let Some(key) = std::env::var_os(KEY_ENV) {
+        key.into_encoded_bytes()
+    } else {
+        anyhow::bail!("no secret key: pass --key-file or set ${KEY_ENV}");
+    };
+
+    let input = matches
+        .get_one::<String>("input")
+        .map_or("-", String::as_str);
+    let reader: Box<dyn BufRead> = if input == "-" {
+        Box::new(std::io::stdin().lock())
+    } else {
+        let file = std::fs::File::open(input).map_err(|e| RNGError::from(e).with_file(input))?;
+        Box::new(std::io::BufReader::new(file))
+    };
+    let mut out = std::io::stdout().lock();
+    let pseudonym = |text: &str| rng.pseudonym(&key, text);
+
+    if let Some(pattern) = matches.get_one::<Regex>("matches") {
+        for line in reader.lines() {
+            let line = line?;
+            let replaced = pattern.replace_all(&line, |caps: &regex::Captures| {
+                let whole = caps.get(0).map_or("", |m| m.as_str());
+                match (caps.get(0), caps.get(1)) {
+                    (Some(m), Some(group)) => format!(
+                        "{}{}{}",
+                        &whole[..group.start() - m.start()],
+                        pseudonym(group.as_str()),
+                        &whole[group.end() - m.start()..]
+                    ),
+                    _ => pseudonym(whole),
+                }
+            });
+            writeln!(out, "{replaced}")?;
+        }
+        return Ok(());
+    }
+
+    let column = matches
+        .get_one::<String>("column")
+        .map_or("", String::as_str);
+    let delimiter = matches.get_one::<char>("delimiter").copied().unwrap_or(',');
+    let mut lines = reader.lines();
+    let header = if matches.get_flag("no-header") {
+        None
+    } else {
+        lines.next().transpose()?
+    };
+    let index = match &header {
+        Some(header) => split_csv(header, delimiter)
+            .iter()
+            .position(|field| field == column)
+            .or_else(|| column_number(column)),
+        None => column_number(column),
+    };
+    let Some(index) = index else {
+        anyhow::bail!("no column {column:?}");
+    };
+    if let Some(header) = header {
+        writeln!(out, "{header}")?;
+    }
+
+    for line in lines {
+        let line = line?;
+        let mut fields = split_csv(&line, delimiter);
+        match fields.get_mut(index) {
+            Some(field) if !field.is_empty() => {
+                *field = pseudonym(field);
+                writeln!(out, "{}", join_csv(&fields, delimiter))?;
+            }
+            _ => writeln!(out, "{line}")?,
+        }
+    }
+    Ok(())
+}
+
+/// The index of a column numbered from 1.
+fn column_number(column: &str) -> Option<usize> {
+    column.parse::<usize>().ok()?.checked_sub(1)
+}
+
+/// Splits a CSV record, held on a single line, into its fields, unquoting them.
+fn split_csv(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Joins fields into a CSV record, quoting those that need it.
+fn join_csv(fields: &[String], delimiter: char) -> String {
+    let mut line = String::new();
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            line.push(delimiter);
+        }
+        if field.contains(delimiter) || field.contains('"') {
+            line.push('"');
+            line.push_str(&field.replace('"', "\"\""));
+            line.push('"');
+        } else {
+            line.push_str(field);
+        }
+    }
+    line
+}
+
+fn identify(matches: &ArgMatches) {
+    let (languages, failed) = LanguageRegistry::from_env().load_all();
+    for (entry, error) in failed {
+        eprintln!("Skipping {} ({}): {error}", entry.name, entry.source);
+    }
+    for name in matches.get_many::<String>("names").unwrap_or_default() {
+        if name.chars().count() > MAX_IDENTIFY_LENGTH {
+            println!("{name}: longer than {MAX_IDENTIFY_LENGTH} characters");
+            continue;
+        }
+        let found = RNG::identify(name, &languages);
+        if found.is_empty() {
+            println!("{name}: no language could have made it");
+            continue;
+        }
+        let width = found
+            .iter()
+            .map(|i| i.language.chars().count())
+            .max()
+            .unwrap_or_default();
+
+        println!("{name}:");
+        for identification in found {
+            println!(
+                "  {:width$}  {:5.1}%",
+                identification.language,
+                identification.probability * 100.0
+            );
+        }
+    }
+}
+
+fn list() {
+    let registry = LanguageRegistry::from_env();
+    let entries = registry.entries();
+    let width = entries
+        .iter()
+        .map(|e| e.name.chars().count())
+        .max()
+        .unwrap_or_default();
+
+    for entry in entries {
+        match entry.source {
+            LanguageSource::BuiltIn(language) if !language.info().production_ready => {
+                println!(
+                    "{:width$}  {} [UNDER CONSTRUCTION]",
+                    entry.name, entry.source
+                );
+            }
+            _ => println!("{:width$}  {}", entry.name, entry.source),
+        }
+    }
+
+    println!();
+    println!("Language directories (${LANGUAGES_PATH_ENV}, then $XDG_DATA_HOME/rng/languages):");
+    for directory in registry.directories() {
+        println!("  {}", directory.display());
+    }
+}
+
 fn get_number(matches: &ArgMatches) -> Option<&usize> {
     matches
         .try_get_one::<usize>("number")
@@ -148,47 +954,226 @@ fn get_number(matches: &ArgMatches) -> Option<&usize> {
 }
 
 fn get_rng(matches: &ArgMatches) -> Result<RNG, RNGError> {
-    let is_russian = matches.get_flag("russian");
-
-    if matches.get_flag("demonic") {
-        RNG::try_from(&Language::Demonic)
-    } else if matches.get_flag("elven") {
-        filter_russian(is_russian, &Language::Elven, &Language::Эльфийский)
-    } else if matches.get_flag("fantasy") {
-        filter_russian(is_russian, &Language::Fantasy, &Language::Фантазия)
-    } else if matches.get_flag("goblin") {
-        filter_russian(is_russian, &Language::Goblin, &Language::Гоблин)
-    } else if matches.get_flag("klingon") {
-        RNG::try_from(&Language::Klingon)
-    } else if matches.get_flag("roman") {
-        filter_russian(is_russian, &Language::Roman, &Language::Римский)
-    } else if matches.get_flag("curse") {
-        RNG::try_from(&Language::Curse)
-    } else if matches.get_flag("german-curse") {
-        RNG::try_from(&Language::GermanCurse)
+    let locale = matches
+        .get_one::<String>("locale")
+        .map(String::as_str)
+        .or(matches.get_flag("russian").then_some("ru"));
+
+    if let Some(name) = matches.get_one::<String>("language") {
+        let registry = LanguageRegistry::from_env();
+        match registry.find(name).map(|entry| entry.source) {
+            Some(LanguageSource::BuiltIn(language)) => localize(language, locale),
+            _ => registry.load(name),
+        }
+    } else if let Some(language) = get_language(matches) {
+        localize(language, locale)
     } else if matches.get_flag("flipmode") {
-        Ok(RNG::random())
+        let selector = get_selector(matches)?;
+        let entry = selector
+            .choose(&mut rand::rng())
+            .ok_or(RNGErrorKind::GenerationError)?;
+        match entry.source {
+            LanguageSource::BuiltIn(language) => localize(language, locale),
+            LanguageSource::File(_) => entry.load(),
+        }
     } else {
         let raw = matches
             .get_one::<String>("raw")
-            .ok_or(RNGError::ParsingError)?;
-        RNG::new_from_file(raw.clone())
+            .ok_or(RNGErrorKind::ParsingError)?;
+        let policy = if matches.get_flag("lenient") {
+            LoadPolicy::Lenient
+        } else {
+            LoadPolicy::Strict
+        };
+        let (rng, report) = if raw == "-" {
+            RNG::load_reader("stdin".to_string(), std::io::stdin().lock(), policy)?
+        } else {
+            RNG::load_file(raw.clone(), policy)?
+        };
+        for issue in report
+            .skipped
+            .iter()
+            .chain(&report.duplicates)
+            .chain(&report.warnings)
+        {
+            eprintln!("{}: {issue}", rng.name);
+        }
+        if !rng.is_valid() {
+            return Err(RNGError::new(RNGErrorKind::InvalidLanguageFile).with_file(raw));
+        }
+        Ok(rng)
     }
 }
 
-fn filter_russian(
-    is_russian: bool,
-    english: &Language,
-    russian: &Language,
-) -> Result<RNG, RNGError> {
-    if is_russian {
-        RNG::try_from(russian)
-    } else {
-        RNG::try_from(english)
+fn get_language(matches: &ArgMatches) -> Option<Language> {
+    [
+        ("demonic", Language::Demonic),
+        ("elven", Language::Elven),
+        ("fantasy", Language::Fantasy),
+        ("goblin", Language::Goblin),
+        ("klingon", Language::Klingon),
+        ("roman", Language::Roman),
+        ("curse", Language::Curse),
+        ("german-curse", Language::GermanCurse),
+    ]
+    .into_iter()
+    .find(|(flag, _)| matches.get_flag(flag))
+    .map(|(_, language)| language)
+}
+
+/// The languages flipmode chooses from: the defaults, or those passed to `--include`, minus
+/// those passed to `--exclude`.
+fn get_selector(matches: &ArgMatches) -> Result<LanguageSelector, RNGError> {
+    let mut selector = match matches.get_many::<(String, u32)>("include") {
+        Some(includes) => {
+            let registry = LanguageRegistry::from_env();
+            let mut selector = LanguageSelector::new();
+            for (name, weight) in includes {
+                selector = match registry.find(name) {
+                    Some(entry) => selector.with_entry(entry, *weight),
+                    None if Path::new(name).is_file() => selector.with_file(name, *weight),
+                    None => {
+                        return Err(RNGError::new(RNGErrorKind::UnknownLanguage).with_text(name));
+                    }
+                };
+            }
+            selector
+        }
+        None => LanguageSelector::default(),
+    };
+    for exclude in matches.get_many::<String>("exclude").unwrap_or_default() {
+        selector = match Language::from_str(exclude) {
+            // Leaves out every variant, as flipmode localizes whichever one it picks.
+            Ok(language) => language
+                .family()
+                .iter()
+                .fold(selector, |selector, variant| {
+                    selector.without(&variant.to_string())
+                }),
+            Err(_) => selector.without(exclude),
+        };
     }
+    Ok(selector)
+}
+
+/// The constraints passed on the command line, or `None` if there aren't any.
+fn get_constraints(matches: &ArgMatches) -> Result<Option<NameConstraints>, RNGError> {
+    let mut constraints = NameConstraints::new();
+    let mut constrained = false;
+    if let Some(min) = matches.get_one::<usize>("min-length") {
+        constraints = constraints.min_length(*min);
+        constrained = true;
+    }
+    if let Some(max) = matches.get_one::<usize>("max-length") {
+        constraints = constraints.max_length(*max);
+        constrained = true;
+    }
+    if let Some(initial) = matches.get_one::<char>("starts-with") {
+        constraints = constraints.starting_with(*initial);
+        constrained = true;
+    }
+    for text in matches.get_many::<String>("contains").unwrap_or_default() {
+        constraints = constraints.containing(text);
+        constrained = true;
+    }
+    for text in matches.get_many::<String>("excludes").unwrap_or_default() {
+        constraints = constraints.excluding(text);
+        constrained = true;
+    }
+    if let Some(pattern) = matches.get_one::<Regex>("matches") {
+        constraints = constraints.matching(pattern.clone());
+        constrained = true;
+    }
+    if let Some(files) = matches.get_many::<String>("blocklist") {
+        let mode = match matches.get_one::<String>("block-mode").map(String::as_str) {
+            Some("exact") => BlockMode::Exact,
+            Some("substring") => BlockMode::Substring,
+            _ => BlockMode::Normalized,
+        };
+        let mut blocklist = Blocklist::new();
+        for file in files {
+            blocklist = blocklist.with_file(file, mode)?;
+        }
+        constraints = constraints.with_blocklist(blocklist);
+        constrained = true;
+    }
+    if matches.contains_id("distinct")
+        || matches.get_flag("phonetic")
+        || matches.contains_id("existing")
+    {
+        let mut diversity = Diversity::new();
+        if let Some(distance) = matches.get_one::<usize>("distinct") {
+            diversity = diversity.within(*distance);
+        }
+        if matches.get_flag("phonetic") {
+            diversity = diversity.phonetic();
+        }
+        for file in matches.get_many::<String>("existing").unwrap_or_default() {
+            diversity = diversity.with_existing_file(file)?;
+        }
+        constraints = constraints.with_diversity(diversity);
+        constrained = true;
+    }
+    Ok(constrained.then_some(constraints))
+}
+
+/// Parses `NAME[:WEIGHT]`, with a default weight of 1. Anything after the last colon that isn't
+/// a number is part of the name, so paths such as `C:\langs\Orcish.txt` can be included.
+fn parse_include(s: &str) -> Result<(String, u32), String> {
+    match s.rsplit_once(':') {
+        Some((name, weight)) if weight.parse::<f64>().is_ok() => weight
+            .parse::<u32>()
+            .map(|weight| (name.to_string(), weight))
+            .map_err(|e| format!("invalid weight {weight:?}: {e}")),
+        _ => Ok((s.to_string(), 1)),
+    }
+}
+
+/// Loads the language's variant for the locale, warning when it doesn't have one.
+fn localize(language: Language, locale: Option<&str>) -> Result<RNG, RNGError> {
+    let Some(locale) = locale else {
+        return RNG::try_from(&language);
+    };
+    if language.with_locale(locale).is_none() {
+        eprintln!(
+            "{language}: no {locale} variant, using {}",
+            language.for_locale(locale)
+        );
+    }
+    RNG::for_locale(&language, locale)
 }
 
 #[test]
 fn verify_cmd() {
     cmd().debug_assert();
 }
+
+#[test]
+fn split_csv_quoted() {
+    assert_eq!(
+        split_csv(r#"1,"Smith, John","say ""hi""",,x"#, ','),
+        vec!["1", "Smith, John", r#"say "hi""#, "", "x"]
+    );
+}
+
+#[test]
+fn join_csv_round_trip() {
+    let line = r#"1,"Smith, John","say ""hi""",,x"#;
+
+    assert_eq!(join_csv(&split_csv(line, ','), ','), line);
+}
+
+#[test]
+fn parse_include_weights() {
+    assert_eq!(parse_include("goblin"), Ok(("goblin".to_string(), 1)));
+    assert_eq!(parse_include("goblin:3"), Ok(("goblin".to_string(), 3)));
+    assert_eq!(
+        parse_include(r"C:\langs\Orcish.txt"),
+        Ok((r"C:\langs\Orcish.txt".to_string(), 1))
+    );
+    assert_eq!(
+        parse_include(r"C:\langs\Orcish.txt:2"),
+        Ok((r"C:\langs\Orcish.txt".to_string(), 2))
+    );
+    assert!(parse_include("goblin:-1").is_err());
+}
@@ -0,0 +1,395 @@
+use crate::rng_syllable::Syllable;
+use crate::rng_syllables::Syllables;
+use crate::{RNG, RNGError, RNGErrorKind};
+
+/// `Blender` builds a hybrid language out of several others, such as a half-elven one from
+/// Elven and Roman.
+///
+/// Each language contributes some or all of its syllable pools, with a weight. Within a pool,
+/// each language is chosen in proportion to its weight, however many syllables it has, so a
+/// language with ten suffixes weighted 1 is as likely to supply the suffix as one with a
+/// hundred. Syllables that appear in more than one language are merged into one.
+///
+/// Syllables are joined across languages by the same `Joiner` rules as within one, and any
+/// syllable that couldn't be followed by a compatible one is left out of the blend. The result
+/// is an ordinary `RNG`, which can be written out with `RNG::to_language_file`.
+///
+/// ```
+/// use rnglib::{Blender, Language, RNG};
+///
+/// let elven = RNG::try_from(&Language::Elven).unwrap();
+/// let goblin = RNG::try_from(&Language::Goblin).unwrap();
+/// let roman = RNG::try_from(&Language::Roman).unwrap();
+///
+/// // Mix Elven and Roman, two parts Elven to one part Roman.
+/// let half_elven = Blender::new("Half-elven")
+///     .with(&elven, 2)
+///     .with(&roman, 1)
+///     .blend()
+///     .unwrap();
+///
+/// // Goblin at the front of the name, Roman at the end.
+/// let goblin_roman = Blender::new("Goblin-roman")
+///     .with_prefixes(&goblin, 1)
+///     .with_centers(&goblin, 1)
+///     .with_centers(&roman, 1)
+///     .with_suffixes(&roman, 1)
+///     .blend()
+///     .unwrap();
+///
+/// println!("{}: {}", half_elven.name, half_elven.generate_name());
+/// println!("{}: {}", goblin_roman.name, goblin_roman.generate_name());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Blender {
+    name: String,
+    prefixes: Vec<(Syllables, u32)>,
+    centers: Vec<(Syllables, u32)>,
+    suffixes: Vec<(Syllables, u32)>,
+}
+
+impl Blender {
+    /// Starts an empty blend, which will be given the name passed in.
+    #[must_use]
+    pub fn new(name: &str) -> Blender {
+        Blender {
+            name: name.to_string(),
+            prefixes: Vec::new(),
+            centers: Vec::new(),
+            suffixes: Vec::new(),
+        }
+    }
+
+    /// Adds every syllable pool of a language.
+    #[must_use]
+    pub fn with(self, rng: &RNG, weight: u32) -> Blender {
+        self.with_prefixes(rng, weight)
+            .with_centers(rng, weight)
+            .with_suffixes(rng, weight)
+    }
+
+    #[must_use]
+    pub fn with_prefixes(mut self, rng: &RNG, weight: u32) -> Blender {
+        self.prefixes.push((rng.prefixes.clone(), weight));
+        self
+    }
+
+    #[must_use]
+    pub fn with_centers(mut self, rng: &RNG, weight: u32) -> Blender {
+        self.centers.push((rng.centers.clone(), weight));
+        self
+    }
+
+    #[must_use]
+    pub fn with_suffixes(mut self, rng: &RNG, weight: u32) -> Blender {
+        self.suffixes.push((rng.suffixes.clone(), weight));
+        self
+    }
+
+    /// Builds the blended language.
+    ///
+    /// # Errors
+    ///
+    /// Errors out with `RNGErrorKind::GenerationError` if the blend ends up without any prefixes,
+    /// centers or suffixes that can be joined together.
+    pub fn blend(&self) -> Result<RNG, RNGError> {
+        let mut rng = RNG::empty(self.name.clone());
+        rng.prefixes = merge(&self.prefixes);
+        rng.centers = merge(&self.centers);
+        rng.suffixes = merge(&self.suffixes);
+        usable(rng)
+    }
+}
+
+/// Prunes the language, then checks that it still has syllables left in every pool.
+///
+/// # Errors
+///
+/// Errors out with `RNGErrorKind::GenerationError` if it ends up without any prefixes, centers
+/// or suffixes that can be joined together.
+pub(crate) fn usable(mut rng: RNG) -> Result<RNG, RNGError> {
+    prune(&mut rng);
+
+    for (syllables, label) in [
+        (&rng.prefixes, "prefix"),
+        (&rng.centers, "center"),
+        (&rng.suffixes, "suffix"),
+    ] {
+        if syllables.is_empty() {
+            return Err(RNGError::new(RNGErrorKind::GenerationError)
+                .with_text(&format!("{} has no usable {label} syllables", rng.name)));
+        }
+    }
+    Ok(rng)
+}
+
+/// Merges the contributions to one syllable pool, scaling each language's syllable weights so
+/// that its share of the pool's total weight matches its own weight.
+fn merge(parts: &[(Syllables, u32)]) -> Syllables {
+    let parts: Vec<(&Syllables, u128, u128)> = parts
+        .iter()
+        .filter(|(syllables, weight)| *weight > 0 && !syllables.is_empty())
+        .map(|(syllables, weight)| {
+            (
+                syllables,
+                u128::from(*weight),
+                u128::from(syllables.total_weight()),
+            )
+        })
+        .collect();
+
+    // Scaling by the least common multiple of the pools' total weights keeps every weight a whole
+    // number. If that gets out of hand, settle for a close approximation.
+    let scale = parts
+        .iter()
+        .try_fold(1u128, |scale, (_, _, total)| lcm(scale, *total))
+        .filter(|scale| *scale <= 1 << 48)
+        .unwrap_or(1 << 32);
+
+    let mut merged: Vec<(Syllable, u128)> = Vec::new();
+    for (syllables, weight, total) in parts {
+        for syllable in syllables.all() {
+            let scaled = (u128::from(syllable.weight) * weight * scale / total).max(1);
+            match merged.iter_mut().find(|(s, _)| same_syllable(s, syllable)) {
+                Some((_, existing)) => *existing += scaled,
+                None => merged.push((syllable.clone(), scaled)),
+            }
+        }
+    }
+
+    let divisor = merged
+        .iter()
+        .fold(0, |divisor, (_, w)| gcd(divisor, *w))
+        .max(1);
+    let largest = merged.iter().map(|(_, w)| *w / divisor).max().unwrap_or(1);
+    let shrink = largest.div_ceil(u128::from(u32::MAX));
+    merged
+        .into_iter()
+        .map(|(mut syllable, weight)| {
+            let weight = (weight / divisor / shrink).max(1);
+            syllable.weight = u32::try_from(weight).unwrap_or(u32::MAX);
+            syllable
+        })
+        .collect()
+}
+
+/// Drops syllables that would leave generation stranded: centers and prefixes that can't be
+/// followed by a suffix, or by a center that can itself go on.
+fn prune(rng: &mut RNG) {
+    let joins_any = |syllable: &Syllable, pool: &Syllables| {
+        pool.all().iter().any(|next| syllable.connects(next))
+    };
+
+    let mut centers: Vec<Syllable> = rng
+        .centers
+        .all()
+        .iter()
+        .filter(|c| joins_any(c, &rng.suffixes))
+        .cloned()
+        .collect();
+    loop {
+        let pool = Syllables::new_from_vector(centers.clone());
+        let before = centers.len();
+        centers.retain(|c| joins_any(c, &pool));
+        if centers.len() == before {
+            break;
+        }
+    }
+    rng.centers = Syllables::new_from_vector(centers);
+
+    rng.prefixes = rng
+        .prefixes
+        .all()
+        .iter()
+        .filter(|p| joins_any(p, &rng.suffixes) && joins_any(p, &rng.centers))
+        .cloned()
+        .collect();
+}
+
+fn same_syllable(a: &Syllable, b: &Syllable) -> bool {
+    a.value == b.value
+        && a.classification == b.classification
+        && a.jprevious == b.jprevious
+        && a.jnext == b.jnext
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+fn lcm(a: u128, b: u128) -> Option<u128> {
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod blend_tests {
+    use super::*;
+    use crate::Language;
+
+    fn micro(name: &str, text: &str) -> RNG {
+        RNG::new_from_str(name.to_string(), text).unwrap()
+    }
+
+    fn weight_of(syllables: &Syllables, value: &str) -> u32 {
+        syllables
+            .all()
+            .iter()
+            .find(|s| s.value == value)
+            .unwrap()
+            .weight
+    }
+
+    #[test]
+    fn blend__merges_pools() {
+        let a = micro("A", "-ka\nri\n+zu");
+        let b = micro("B", "-to\nma\n+dan");
+
+        let rng = Blender::new("AB").with(&a, 1).with(&b, 1).blend().unwrap();
+
+        assert_eq!(rng.name, "AB");
+        assert_eq!(rng.prefixes.len(), 2);
+        assert_eq!(rng.centers.len(), 2);
+        assert_eq!(rng.suffixes.len(), 2);
+    }
+
+    #[test]
+    fn blend__weights_languages_not_syllables() {
+        // B has three times as many prefixes, but A is weighted twice as heavily.
+        let a = micro("A", "-ka\nri\n+zu");
+        let b = micro("B", "-to\n-po\n-lo\nma\n+dan");
+
+        let rng = Blender::new("AB").with(&a, 2).with(&b, 1).blend().unwrap();
+
+        assert_eq!(weight_of(&rng.prefixes, "ka"), 6);
+        assert_eq!(weight_of(&rng.prefixes, "to"), 1);
+        assert_eq!(rng.prefixes.total_weight(), 9);
+    }
+
+    #[test]
+    fn blend__keeps_syllable_weights() {
+        let a = micro("A", "-ka *3\n-ta\nri\n+zu");
+
+        let rng = Blender::new("A").with(&a, 5).blend().unwrap();
+
+        assert_eq!(weight_of(&rng.prefixes, "ka"), 3);
+        assert_eq!(weight_of(&rng.prefixes, "ta"), 1);
+    }
+
+    #[test]
+    fn blend__merges_shared_syllables() {
+        let a = micro("A", "-ka\nri\n+zu");
+        let b = micro("B", "-ka\n-to\nri\n+zu");
+
+        let rng = Blender::new("AB").with(&a, 1).with(&b, 1).blend().unwrap();
+
+        assert_eq!(rng.prefixes.len(), 2);
+        assert_eq!(weight_of(&rng.prefixes, "ka"), 3);
+        assert_eq!(weight_of(&rng.prefixes, "to"), 1);
+        assert_eq!(rng.centers.len(), 1);
+    }
+
+    #[test]
+    fn blend__positional() {
+        let goblin = RNG::try_from(&Language::Goblin).unwrap();
+        let roman = RNG::try_from(&Language::Roman).unwrap();
+
+        let rng = Blender::new("Goblin-roman")
+            .with_prefixes(&goblin, 1)
+            .with_centers(&roman, 1)
+            .with_suffixes(&roman, 1)
+            .blend()
+            .unwrap();
+
+        assert!(
+            rng.prefixes.all().iter().all(|p| goblin
+                .prefixes
+                .all()
+                .iter()
+                .any(|g| g.value == p.value))
+        );
+        assert!(
+            rng.suffixes.all().iter().all(|s| roman
+                .suffixes
+                .all()
+                .iter()
+                .any(|r| r.value == s.value))
+        );
+        for _ in 0..200 {
+            let _ = rng.generate_name();
+        }
+    }
+
+    #[test]
+    fn blend__prunes_dead_ends() {
+        // "ik +v" needs a vowel next, but nothing starts with one.
+        let a = micro("A", "-ka\n-ik +v\nri\nor +v\n+zu");
+
+        let rng = Blender::new("A").with(&a, 1).blend().unwrap();
+
+        assert_eq!(rng.prefixes.len(), 1);
+        assert_eq!(rng.centers.len(), 1);
+    }
+
+    #[test]
+    fn blend__nothing_joins() {
+        let a = micro("A", "-ik +v\nri\n+zu");
+
+        let error = Blender::new("A").with(&a, 1).blend().unwrap_err();
+
+        assert_eq!(error.kind(), RNGErrorKind::GenerationError);
+        assert_eq!(error.text(), Some("A has no usable prefix syllables"));
+    }
+
+    #[test]
+    fn blend__zero_weight_is_left_out() {
+        let a = micro("A", "-ka\nri\n+zu");
+        let b = micro("B", "-to\nma\n+dan");
+
+        let rng = Blender::new("A").with(&a, 1).with(&b, 0).blend().unwrap();
+
+        assert_eq!(rng.syllables().len(), 3);
+    }
+
+    #[test]
+    fn blend__built_in_languages_generate() {
+        let languages: Vec<RNG> = Language::all()
+            .iter()
+            .map(|l| RNG::try_from(l).unwrap())
+            .collect();
+
+        let rng = languages
+            .iter()
+            .fold(Blender::new("Everything"), |blender, rng| {
+                blender.with(rng, 1)
+            })
+            .blend()
+            .unwrap();
+
+        assert!(rng.is_valid());
+        for _ in 0..500 {
+            let _ = rng.generate_name();
+        }
+    }
+
+    #[test]
+    fn blend__exports_as_language_file() {
+        let elven = RNG::try_from(&Language::Elven).unwrap();
+        let roman = RNG::try_from(&Language::Roman).unwrap();
+        let rng = Blender::new("Half-elven")
+            .with(&elven, 2)
+            .with(&roman, 1)
+            .blend()
+            .unwrap();
+
+        let reloaded = RNG::new_from_str(rng.name.clone(), &rng.to_language_file()).unwrap();
+
+        assert_eq!(reloaded, rng);
+    }
+
+    #[test]
+    fn lcm__overflow() {
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(u128::MAX, 2), None);
+    }
+}
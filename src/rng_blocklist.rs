@@ -0,0 +1,323 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::RNGError;
+
+/// How a blocklisted word is matched against a generated name.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BlockMode {
+    /// The whole name is the word, ignoring case.
+    Exact,
+    /// The name contains the word, ignoring case.
+    Substring,
+    /// The name contains the word once both have been normalized: lowercased, with diacritics
+    /// and anything other than letters removed, and leetspeak spelled out, so that `Sh1t` and
+    /// `shît` both match `shit`.
+    Normalized,
+}
+
+/// `Blocklist` keeps real words and profanity out of generated names. Words are loaded from word
+/// lists, one word per line, where blank lines and lines starting with `#` are ignored.
+///
+/// A blocklist is applied during generation by adding it to a set of `NameConstraints`, and it
+/// counts how many otherwise acceptable names it has rejected.
+///
+/// ```
+/// use rnglib::{BlockMode, Blocklist, Language, NameConstraints, RNG};
+///
+/// let blocklist = Blocklist::new()
+///     .with_word("dragon", BlockMode::Exact)
+///     .with_word("orc", BlockMode::Normalized);
+/// let constraints = NameConstraints::new().with_blocklist(blocklist);
+///
+/// let rng = RNG::try_from(&Language::Goblin).unwrap();
+/// let name = rng.generate_name_with(&constraints).unwrap();
+///
+/// assert!(!name.to_lowercase().contains("orc"));
+/// ```
+#[derive(Debug, Default)]
+pub struct Blocklist {
+    exact: Vec<String>,
+    substrings: Vec<String>,
+    normalized: Vec<String>,
+    rejected: AtomicUsize,
+}
+
+impl Clone for Blocklist {
+    fn clone(&self) -> Self {
+        Blocklist {
+            exact: self.exact.clone(),
+            substrings: self.substrings.clone(),
+            normalized: self.normalized.clone(),
+            rejected: AtomicUsize::new(self.rejected()),
+        }
+    }
+}
+
+impl Blocklist {
+    #[must_use]
+    pub fn new() -> Blocklist {
+        Blocklist::default()
+    }
+
+    #[must_use]
+    pub fn with_word(mut self, word: &str, mode: BlockMode) -> Blocklist {
+        self.add(word, mode);
+        self
+    }
+
+    /// Adds every word in the word list.
+    #[must_use]
+    pub fn with_words(mut self, list: &str, mode: BlockMode) -> Blocklist {
+        for word in list.lines().map(str::trim) {
+            if !word.is_empty() && !word.starts_with('#') {
+                self.add(word, mode);
+            }
+        }
+        self
+    }
+
+    /// Adds every word in a word list file.
+    ///
+    /// # Errors
+    ///
+    /// Errors out with `RNGErrorKind::ReadError` if the file can't be read.
+    pub fn with_file(self, path: impl AsRef<Path>, mode: BlockMode) -> Result<Blocklist, RNGError> {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(list) => Ok(self.with_words(&list, mode)),
+            Err(e) => Err(RNGError::from(e).with_file(&path.to_string_lossy())),
+        }
+    }
+
+    fn add(&mut self, word: &str, mode: BlockMode) {
+        let (words, word) = match mode {
+            BlockMode::Exact => (&mut self.exact, word.to_lowercase()),
+            BlockMode::Substring => (&mut self.substrings, word.to_lowercase()),
+            BlockMode::Normalized => (&mut self.normalized, normalize(word)),
+        };
+        if !word.is_empty() && !words.contains(&word) {
+            words.push(word);
+        }
+    }
+
+    /// How many words are on the list.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.exact.len() + self.substrings.len() + self.normalized.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// True if the name matches any of the words.
+    #[must_use]
+    pub fn blocks(&self, name: &str) -> bool {
+        self.exact.contains(&name.to_lowercase()) || self.blocks_within(name)
+    }
+
+    /// True if the text contains a word matched as a substring, so that every name starting with
+    /// it is blocked too. Unlike `rejects`, nothing is counted, since no name has been rejected
+    /// yet.
+    pub(crate) fn blocks_within(&self, name: &str) -> bool {
+        let lower = name.to_lowercase();
+        if self
+            .substrings
+            .iter()
+            .any(|word| lower.contains(word.as_str()))
+        {
+            return true;
+        }
+        if self.normalized.is_empty() {
+            return false;
+        }
+        let normalized = normalize(name);
+        self.normalized
+            .iter()
+            .any(|word| normalized.contains(word.as_str()))
+    }
+
+    /// How many generated names the blocklist has rejected.
+    #[must_use]
+    pub fn rejected(&self) -> usize {
+        self.rejected.load(Ordering::Relaxed)
+    }
+
+    pub fn reset(&self) {
+        self.rejected.store(0, Ordering::Relaxed);
+    }
+
+    /// Checks a generated name, counting it if it is rejected.
+    pub(crate) fn rejects(&self, name: &str) -> bool {
+        let blocked = self.blocks(name);
+        if blocked {
+            self.rejected.fetch_add(1, Ordering::Relaxed);
+        }
+        blocked
+    }
+}
+
+/// Lowercases the text, spells out leetspeak, strips diacritics and drops everything that
+/// isn't a letter.
+fn normalize(text: &str) -> String {
+    text.chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| unleet(c).unwrap_or(c))
+        .map(fold)
+        .filter(|c| c.is_alphabetic())
+        .collect()
+}
+
+fn unleet(c: char) -> Option<char> {
+    Some(match c {
+        '0' => 'o',
+        '1' | '!' => 'i',
+        '3' => 'e',
+        '4' | '@' => 'a',
+        '5' | '$' => 's',
+        '7' | '+' => 't',
+        '8' => 'b',
+        '9' => 'g',
+        _ => return None,
+    })
+}
+
+/// The letter without its diacritics, for lowercase Latin and Cyrillic letters.
+fn fold(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => 'a',
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => 'c',
+        'ď' | 'đ' => 'd',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => 'e',
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => 'g',
+        'ĥ' | 'ħ' => 'h',
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => 'i',
+        'ĵ' => 'j',
+        'ķ' => 'k',
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => 'l',
+        'ñ' | 'ń' | 'ņ' | 'ň' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => 'o',
+        'ŕ' | 'ŗ' | 'ř' => 'r',
+        'ś' | 'ŝ' | 'ş' | 'š' => 's',
+        'ţ' | 'ť' | 'ŧ' => 't',
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => 'u',
+        'ŵ' => 'w',
+        'ý' | 'ÿ' | 'ŷ' => 'y',
+        'ź' | 'ż' | 'ž' => 'z',
+        'ё' => 'е',
+        'й' => 'и',
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod blocklist_tests {
+    use super::*;
+    use crate::RNGErrorKind;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("Drake", true)]
+    #[case("DRAKE", true)]
+    #[case("Drakes", false)]
+    #[case("Mandrake", false)]
+    fn blocks__exact(#[case] name: &str, #[case] expected: bool) {
+        let blocklist = Blocklist::new().with_word("drake", BlockMode::Exact);
+
+        assert_eq!(blocklist.blocks(name), expected);
+    }
+
+    #[rstest]
+    #[case("Drake", true)]
+    #[case("Mandrakes", true)]
+    #[case("Dr4ke", false)]
+    #[case("Drak", false)]
+    fn blocks__substring(#[case] name: &str, #[case] expected: bool) {
+        let blocklist = Blocklist::new().with_word("DRAKE", BlockMode::Substring);
+
+        assert_eq!(blocklist.blocks(name), expected);
+    }
+
+    #[rstest]
+    #[case("Mandrake", true)]
+    #[case("Dr4ke", true)]
+    #[case("Dräké", true)]
+    #[case("D.r.a.k.e", true)]
+    #[case("Drak", false)]
+    fn blocks__normalized(#[case] name: &str, #[case] expected: bool) {
+        let blocklist = Blocklist::new().with_word("drake", BlockMode::Normalized);
+
+        assert_eq!(blocklist.blocks(name), expected);
+    }
+
+    #[test]
+    fn blocks__normalized_word() {
+        let blocklist = Blocklist::new().with_word("Sh1t", BlockMode::Normalized);
+
+        assert!(blocklist.blocks("Ashita"));
+    }
+
+    #[test]
+    fn blocks__cyrillic() {
+        let blocklist = Blocklist::new().with_word("ёж", BlockMode::Normalized);
+
+        assert!(blocklist.blocks("Ежик"));
+    }
+
+    #[test]
+    fn with_words() {
+        let blocklist = Blocklist::new().with_words(
+            "# animals\ndrake\n\n  wyrm  \ndrake\n",
+            BlockMode::Substring,
+        );
+
+        assert_eq!(blocklist.len(), 2);
+        assert!(blocklist.blocks("Wyrmling"));
+    }
+
+    #[test]
+    fn with_file__missing() {
+        let error = Blocklist::new()
+            .with_file("src/languages/none.txt", BlockMode::Exact)
+            .unwrap_err();
+
+        assert_eq!(error.kind(), RNGErrorKind::ReadError);
+        assert_eq!(error.file(), Some("src/languages/none.txt"));
+    }
+
+    #[test]
+    fn rejects__counts() {
+        let blocklist = Blocklist::new().with_word("drake", BlockMode::Exact);
+
+        assert!(blocklist.rejects("Drake"));
+        assert!(!blocklist.rejects("Wyrm"));
+        assert!(blocklist.rejects("drake"));
+        assert_eq!(blocklist.rejected(), 2);
+
+        blocklist.reset();
+
+        assert_eq!(blocklist.rejected(), 0);
+    }
+
+    #[test]
+    fn blocks_within() {
+        let blocklist = Blocklist::new()
+            .with_word("kar", BlockMode::Exact)
+            .with_word("z0", BlockMode::Normalized);
+
+        assert!(!blocklist.blocks_within("kar"));
+        assert!(blocklist.blocks_within("kazo"));
+        assert_eq!(blocklist.rejected(), 0);
+    }
+
+    #[rstest]
+    #[case("Sh1t!", "shiti")]
+    #[case("Ça Va", "cava")]
+    #[case("Ёлка", "елка")]
+    fn normalize__cases(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(normalize(input), expected);
+    }
+}
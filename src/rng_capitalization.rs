@@ -0,0 +1,140 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// How a language capitalizes the names it generates. Set in a language file with the
+/// `@capitalize` directive, such as `@capitalize apostrophe`.
+///
+/// ```
+/// use rnglib::Capitalization;
+///
+/// assert_eq!(Capitalization::FirstLetter.apply("qo'nos"), "Qo'nos");
+/// assert_eq!(Capitalization::AfterApostrophe.apply("qo'nos"), "Qo'Nos");
+/// assert_eq!(Capitalization::Preserve.apply("d'Arc"), "d'Arc");
+/// assert_eq!(Capitalization::None.apply("MacKay"), "mackay");
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Capitalization {
+    /// Only the first letter is a capital, as in `Kragog` or `Эльдар`.
+    #[default]
+    FirstLetter,
+    /// The first letter, and any letter straight after an apostrophe, are capitals, as in
+    /// `Qo'Nos`.
+    AfterApostrophe,
+    /// The name is left just as its syllables are written in the language file, for names such
+    /// as `MacKay` or `d'Arc`.
+    Preserve,
+    /// The name is all lowercase.
+    None,
+}
+
+impl Capitalization {
+    /// Capitalizes a name according to the policy.
+    #[must_use]
+    pub fn apply(&self, name: &str) -> String {
+        if *self == Capitalization::Preserve {
+            return name.to_string();
+        }
+        let mut capitalized = String::with_capacity(name.len());
+        let mut capital = *self != Capitalization::None;
+        for c in name.chars() {
+            if capital && c.is_alphabetic() {
+                capitalized.extend(c.to_uppercase());
+                capital = false;
+            } else {
+                capitalized.extend(c.to_lowercase());
+            }
+            if *self == Capitalization::AfterApostrophe && c == '\'' {
+                capital = true;
+            }
+        }
+        capitalized
+    }
+}
+
+impl fmt::Display for Capitalization {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Capitalization::FirstLetter => write!(f, "first"),
+            Capitalization::AfterApostrophe => write!(f, "apostrophe"),
+            Capitalization::Preserve => write!(f, "preserve"),
+            Capitalization::None => write!(f, "none"),
+        }
+    }
+}
+
+impl FromStr for Capitalization {
+    type Err = BadCapitalization;
+
+    /// Parses a policy as it is written in language files, ignoring case: `first`, `apostrophe`,
+    /// `preserve` or `none`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "first" => Ok(Capitalization::FirstLetter),
+            "apostrophe" => Ok(Capitalization::AfterApostrophe),
+            "preserve" => Ok(Capitalization::Preserve),
+            "none" => Ok(Capitalization::None),
+            _ => Err(BadCapitalization),
+        }
+    }
+}
+
+/// Returned when a string doesn't name a `Capitalization`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BadCapitalization;
+
+impl fmt::Display for BadCapitalization {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid Capitalization")
+    }
+}
+
+impl std::error::Error for BadCapitalization {}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod capitalization_tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Capitalization::FirstLetter, "kragog", "Kragog")]
+    #[case(Capitalization::FirstLetter, "MacKay", "Mackay")]
+    #[case(Capitalization::FirstLetter, "'aelthas", "'Aelthas")]
+    #[case(Capitalization::FirstLetter, "гоблин", "Гоблин")]
+    #[case(Capitalization::AfterApostrophe, "qapla'zo", "Qapla'Zo")]
+    #[case(Capitalization::AfterApostrophe, "qo'", "Qo'")]
+    #[case(Capitalization::AfterApostrophe, "d'arc", "D'Arc")]
+    #[case(Capitalization::Preserve, "d'Arc", "d'Arc")]
+    #[case(Capitalization::Preserve, "macKay", "macKay")]
+    #[case(Capitalization::None, "Эльдар", "эльдар")]
+    #[case(Capitalization::FirstLetter, "", "")]
+    fn apply(#[case] policy: Capitalization, #[case] name: &str, #[case] expected: &str) {
+        assert_eq!(policy.apply(name), expected);
+    }
+
+    #[rstest]
+    #[case("first", Capitalization::FirstLetter)]
+    #[case("Apostrophe", Capitalization::AfterApostrophe)]
+    #[case("PRESERVE", Capitalization::Preserve)]
+    #[case("none", Capitalization::None)]
+    fn from_str(#[case] input: &str, #[case] expected: Capitalization) {
+        assert_eq!(Capitalization::from_str(input), Ok(expected));
+    }
+
+    #[test]
+    fn from_str__invalid() {
+        assert_eq!(Capitalization::from_str("title"), Err(BadCapitalization));
+    }
+
+    #[test]
+    fn from_str__round_trips_display() {
+        for policy in [
+            Capitalization::FirstLetter,
+            Capitalization::AfterApostrophe,
+            Capitalization::Preserve,
+            Capitalization::None,
+        ] {
+            assert_eq!(Capitalization::from_str(&policy.to_string()), Ok(policy));
+        }
+    }
+}
@@ -0,0 +1,237 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::Transliteration;
+
+/// Writes names as ASCII identifiers, for naming things like databases, branches and
+/// containers.
+///
+/// Names are split into words at spaces, hyphens, underscores and anything else that isn't a
+/// letter or a digit. Cyrillic is transliterated, see `Transliteration::Bgn`, diacritics are
+/// folded, so that `ë` becomes `e` and `ß` becomes `ss`, and apostrophes, as in the Klingon
+/// `Q'onos`, are dropped.
+///
+/// ```
+/// use rnglib::NameCase;
+///
+/// assert_eq!(NameCase::Slug.apply("Q'onos Kahless"), "qonos-kahless");
+/// assert_eq!(NameCase::Snake.apply("Q'onos Kahless"), "qonos_kahless");
+/// assert_eq!(NameCase::Camel.apply("Zoë Гоблин"), "ZoeGoblin");
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum NameCase {
+    /// Lowercase words separated by hyphens, as in `kragog-zrashagul`. Unlike the other cases,
+    /// words are kept whole, rather than split at capitals within them, so `MacKay` stays
+    /// `mackay`.
+    Slug,
+    /// `snake_case`
+    Snake,
+    /// `kebab-case`
+    Kebab,
+    /// `CamelCase`
+    Camel,
+    /// `SCREAMING_CASE`
+    Screaming,
+}
+
+impl NameCase {
+    /// Writes the name as an identifier. A name made of several words, such as `Q'onos Kahless`,
+    /// becomes a single identifier, so apply it to each name on its own.
+    #[must_use]
+    pub fn apply(&self, name: &str) -> String {
+        let words = self.words(name);
+        match self {
+            NameCase::Slug | NameCase::Kebab => words.join("-"),
+            NameCase::Snake => words.join("_"),
+            NameCase::Screaming => words.join("_").to_ascii_uppercase(),
+            NameCase::Camel => words
+                .iter()
+                .map(|word| {
+                    let (first, rest) = word.split_at(1);
+                    first.to_ascii_uppercase() + rest
+                })
+                .collect(),
+        }
+    }
+
+    /// The lowercase ASCII words in the name, leaving out any that are left empty.
+    fn words(self, name: &str) -> Vec<String> {
+        let mut words = Vec::new();
+        let mut word = String::new();
+        let mut after_lowercase = false;
+        for c in Transliteration::Bgn.transliterate(name).chars() {
+            if APOSTROPHES.contains(&c) {
+                continue;
+            }
+            let hump = self != NameCase::Slug && after_lowercase && c.is_uppercase();
+            if !c.is_alphanumeric() || hump {
+                words.push(std::mem::take(&mut word));
+            }
+            after_lowercase = c.is_lowercase();
+            for lower in c.to_lowercase() {
+                fold(lower, &mut word);
+            }
+        }
+        words.push(word);
+        words.retain(|word| !word.is_empty());
+        words
+    }
+}
+
+impl fmt::Display for NameCase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NameCase::Slug => write!(f, "slug"),
+            NameCase::Snake => write!(f, "snake_case"),
+            NameCase::Kebab => write!(f, "kebab-case"),
+            NameCase::Camel => write!(f, "CamelCase"),
+            NameCase::Screaming => write!(f, "SCREAMING_CASE"),
+        }
+    }
+}
+
+impl FromStr for NameCase {
+    type Err = BadNameCase;
+
+    /// Parses a case by name, ignoring case, hyphens and underscores, with or without `case` on
+    /// the end, so `snake`, `snake_case`, `Kebab-Case` and `SCREAMING_CASE` are all accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name: String = s
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_' | ' '))
+            .flat_map(char::to_lowercase)
+            .collect();
+        match name.strip_suffix("case").unwrap_or(&name) {
+            "slug" => Ok(NameCase::Slug),
+            "snake" => Ok(NameCase::Snake),
+            "kebab" => Ok(NameCase::Kebab),
+            "camel" => Ok(NameCase::Camel),
+            "screaming" => Ok(NameCase::Screaming),
+            _ => Err(BadNameCase),
+        }
+    }
+}
+
+/// Returned when a string doesn't name a `NameCase`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BadNameCase;
+
+impl fmt::Display for BadNameCase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid NameCase")
+    }
+}
+
+impl std::error::Error for BadNameCase {}
+
+static APOSTROPHES: [char; 4] = ['\'', '’', 'ʼ', '`'];
+
+/// Adds the lowercase letter to the word as plain ASCII, leaving it out if there's no way to.
+fn fold(c: char, word: &mut String) {
+    if c.is_ascii_alphanumeric() {
+        word.push(c);
+        return;
+    }
+    let folded = match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => "",
+    };
+    word.push_str(folded);
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod case_tests {
+    use super::*;
+    use crate::{Language, RNG};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(NameCase::Slug, "Kragog Zrashagul", "kragog-zrashagul")]
+    #[case(NameCase::Snake, "Kragog Zrashagul", "kragog_zrashagul")]
+    #[case(NameCase::Kebab, "Kragog Zrashagul", "kragog-zrashagul")]
+    #[case(NameCase::Camel, "Kragog Zrashagul", "KragogZrashagul")]
+    #[case(NameCase::Screaming, "Kragog Zrashagul", "KRAGOG_ZRASHAGUL")]
+    #[case(NameCase::Slug, "MacKay", "mackay")]
+    #[case(NameCase::Kebab, "MacKay", "mac-kay")]
+    #[case(NameCase::Snake, "d'Arc", "d_arc")]
+    #[case(NameCase::Slug, "d'Arc", "darc")]
+    #[case(NameCase::Slug, "  Q'onoS -- Kahless!  ", "qonos-kahless")]
+    #[case(NameCase::Snake, "Ærwÿn Straße", "aerwyn_strasse")]
+    #[case(NameCase::Kebab, "Щукин 2", "shchukin-2")]
+    #[case(NameCase::Camel, "ŝ ʺ", "S")]
+    #[case(NameCase::Slug, "", "")]
+    fn apply(#[case] case: NameCase, #[case] name: &str, #[case] expected: &str) {
+        assert_eq!(case.apply(name), expected);
+    }
+
+    #[rstest]
+    #[case(Language::Klingon)]
+    #[case(Language::GermanCurse)]
+    #[case(Language::Эльфийский)]
+    fn apply__ascii_identifiers(#[case] language: Language) {
+        let rng = RNG::try_from(&language).unwrap();
+
+        for name in rng.generate_names(20, false) {
+            let slug = NameCase::Slug.apply(&name);
+            assert!(
+                slug.chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-'),
+                "{name}: {slug}"
+            );
+            assert!(NameCase::Camel.apply(&name).is_ascii(), "{name}");
+        }
+    }
+
+    #[rstest]
+    #[case("slug", NameCase::Slug)]
+    #[case("snake", NameCase::Snake)]
+    #[case("snake_case", NameCase::Snake)]
+    #[case("Kebab-Case", NameCase::Kebab)]
+    #[case("camel", NameCase::Camel)]
+    #[case("SCREAMING_CASE", NameCase::Screaming)]
+    fn from_str(#[case] input: &str, #[case] expected: NameCase) {
+        assert_eq!(NameCase::from_str(input), Ok(expected));
+    }
+
+    #[test]
+    fn from_str__invalid() {
+        assert_eq!(NameCase::from_str("title"), Err(BadNameCase));
+        assert_eq!(NameCase::from_str("case"), Err(BadNameCase));
+    }
+
+    #[test]
+    fn from_str__round_trips_display() {
+        for case in [
+            NameCase::Slug,
+            NameCase::Snake,
+            NameCase::Kebab,
+            NameCase::Camel,
+            NameCase::Screaming,
+        ] {
+            assert_eq!(NameCase::from_str(&case.to_string()), Ok(case));
+        }
+    }
+}
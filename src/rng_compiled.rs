@@ -0,0 +1,524 @@
+//! `rng_compiled`: A compact, versioned binary representation of an already classified language.
+//!
+//! Parsing a text language file runs every line through `FULL_RE`, `PREFIX_RE` and `SUFFIX_RE`. A compiled
+//! file stores the result of that work, so loading it is a single pass over a byte slice with no regular
+//! expressions involved. Syllable values are validated as UTF-8 in place and then copied once, into the
+//! `String`s of the resulting `RNG`. The format is not read zero-copy or memory-mapped: an `RNG` owns its
+//! syllables, so they would be copied out of the mapping anyway.
+//!
+//! LAYOUT (all integers little endian):
+//!
+//! ```text
+//! magic      4 bytes   "RNGC"
+//! version    u8        currently 2; version 1 files, which end after the suffixes and have no
+//!                      weights, still load
+//! name       u16 length, followed by that many bytes of UTF-8
+//! prefixes   section
+//! centers    section
+//! suffixes   section
+//! spellings  u32 count, followed by count spellings
+//! capitalize u8 (0 first letter, 1 after apostrophes, 2 preserve, 3 none)
+//!
+//! section    u32 count, followed by count syllables
+//! syllable   u8 joiners (jprevious bits in the high nibble, jnext bits in the low nibble),
+//!            u8 length, followed by that many bytes of UTF-8. If the length's high bit is set, the
+//!            length is in the low 7 bits and the syllable's u32 weight follows; otherwise the
+//!            weight is 1
+//! spelling   u8 script (0 for Latin, 1 for Cyrillic), then the phoneme and its spelling, each a
+//!            u16 length followed by that many bytes of UTF-8
+//! ```
+
+use crate::rng_joiner::Joiner;
+use crate::rng_syllable::{Classification, Syllable};
+use crate::rng_syllables::Syllables;
+use crate::{Capitalization, Orthography, RNG, RNGError, RNGErrorKind, Script};
+use std::fmt;
+
+/// Magic bytes that open every compiled language file.
+pub const MAGIC: &[u8; 4] = b"RNGC";
+/// Current version of the compiled language format.
+pub const VERSION: u8 = 2;
+/// File extension used for compiled language files.
+pub const EXTENSION: &str = "rngc";
+
+/// Writes the language in the compiled format, with the layout given in the module docs.
+///
+/// # Errors
+///
+/// Errors out with `RNGErrorKind::WriteError` if a syllable is 128 bytes or longer, or the name,
+/// a phoneme or a spelling is 64KiB or longer, since their lengths don't fit in the format.
+pub fn compile(rng: &RNG) -> Result<Vec<u8>, RNGError> {
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    out.push(VERSION);
+    write_str16(&mut out, rng.name.as_str())?;
+    for syllables in [&rng.prefixes, &rng.centers, &rng.suffixes] {
+        write_section(&mut out, syllables)?;
+    }
+    write_spellings(&mut out, &rng.orthography)?;
+    out.push(match rng.capitalization {
+        Capitalization::FirstLetter => 0,
+        Capitalization::AfterApostrophe => 1,
+        Capitalization::Preserve => 2,
+        Capitalization::None => 3,
+    });
+    Ok(out)
+}
+
+/// Returns true if the bytes start with the compiled language magic header.
+pub fn is_compiled(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// Decodes a compiled language file.
+///
+/// # Errors
+///
+/// Errors out if the header is missing, the version is unsupported, or the data is truncated or corrupt.
+pub fn load(bytes: &[u8]) -> Result<RNG, RNGError> {
+    let mut reader = Reader::new(bytes);
+
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(FormatError::NotCompiled.into());
+    }
+    let version = reader.u8()?;
+    if !(1..=VERSION).contains(&version) {
+        return Err(FormatError::UnsupportedVersion(version).into());
+    }
+    // Version 1 files have no weights, spellings or capitalization.
+    let current = version == VERSION;
+
+    let mut rng = RNG::empty(reader.str16()?.to_string());
+    rng.prefixes = reader.section(&Classification::Prefix, current)?;
+    rng.centers = reader.section(&Classification::Center, current)?;
+    rng.suffixes = reader.section(&Classification::Suffix, current)?;
+    if current {
+        rng.orthography = reader.spellings()?;
+        rng.capitalization = match reader.u8()? {
+            0 => Capitalization::FirstLetter,
+            1 => Capitalization::AfterApostrophe,
+            2 => Capitalization::Preserve,
+            3 => Capitalization::None,
+            capitalization => {
+                return Err(FormatError::UnknownCapitalization(capitalization).into());
+            }
+        };
+    }
+
+    if reader.is_empty() {
+        Ok(rng)
+    } else {
+        Err(FormatError::TrailingBytes.into())
+    }
+}
+
+/// High bit of a syllable's length byte, set when a weight follows the syllable.
+const WEIGHTED: u8 = 0x80;
+
+/// Describes what is wrong with a compiled language file. Surfaces as the source of an
+/// `RNGErrorKind::InvalidLanguageFile` error.
+#[derive(Debug)]
+enum FormatError {
+    NotCompiled,
+    UnsupportedVersion(u8),
+    Truncated,
+    TrailingBytes,
+    InvalidUtf8(std::str::Utf8Error),
+    UnknownScript(u8),
+    UnknownCapitalization(u8),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FormatError::NotCompiled => write!(f, "missing compiled language header"),
+            FormatError::UnsupportedVersion(v) => {
+                write!(f, "unsupported compiled format version {v}")
+            }
+            FormatError::Truncated => write!(f, "compiled language is truncated"),
+            FormatError::TrailingBytes => write!(f, "unexpected bytes after the last section"),
+            FormatError::InvalidUtf8(e) => write!(f, "syllable is not valid UTF-8: {e}"),
+            FormatError::UnknownScript(script) => write!(f, "unknown script {script}"),
+            FormatError::UnknownCapitalization(capitalization) => {
+                write!(f, "unknown capitalization {capitalization}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+impl From<FormatError> for RNGError {
+    fn from(error: FormatError) -> Self {
+        RNGError::new(RNGErrorKind::InvalidLanguageFile).with_source(error)
+    }
+}
+
+fn write_section(out: &mut Vec<u8>, syllables: &Syllables) -> Result<(), RNGError> {
+    write_count(out, syllables.len())?;
+    for syllable in syllables.all() {
+        out.push(syllable.jprevious.bits() << 4 | syllable.jnext.bits());
+        let len = u8::try_from(syllable.value.len())
+            .ok()
+            .filter(|len| *len < WEIGHTED)
+            .ok_or_else(|| too_long(&syllable.value, "syllables must be shorter than 128 bytes"))?;
+        if syllable.weight == 1 {
+            out.push(len);
+            out.extend_from_slice(syllable.value.as_bytes());
+        } else {
+            out.push(len | WEIGHTED);
+            out.extend_from_slice(syllable.value.as_bytes());
+            out.extend_from_slice(&syllable.weight.to_le_bytes());
+        }
+    }
+    Ok(())
+}
+
+fn write_spellings(out: &mut Vec<u8>, orthography: &Orthography) -> Result<(), RNGError> {
+    let spellings: Vec<_> = orthography.spellings().collect();
+    write_count(out, spellings.len())?;
+    for (script, phoneme, spelling) in spellings {
+        out.push(match script {
+            Script::Latin => 0,
+            Script::Cyrillic => 1,
+        });
+        write_str16(out, phoneme)?;
+        write_str16(out, spelling)?;
+    }
+    Ok(())
+}
+
+fn write_count(out: &mut Vec<u8>, count: usize) -> Result<(), RNGError> {
+    let count = u32::try_from(count).map_err(|_| {
+        RNGError::new(RNGErrorKind::WriteError).with_text("too many syllables or spellings")
+    })?;
+    out.extend_from_slice(&count.to_le_bytes());
+    Ok(())
+}
+
+fn write_str16(out: &mut Vec<u8>, s: &str) -> Result<(), RNGError> {
+    let len = u16::try_from(s.len()).map_err(|_| {
+        too_long(
+            s,
+            "names, phonemes and spellings must be shorter than 64KiB",
+        )
+    })?;
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(s.as_bytes());
+    Ok(())
+}
+
+/// The error for a string too long to be written, naming the first few characters of it.
+fn too_long(s: &str, limit: &str) -> RNGError {
+    let start: String = s.chars().take(20).collect();
+    RNGError::new(RNGErrorKind::WriteError).with_text(&format!("{start}...: {limit}"))
+}
+
+/// Cursor over a borrowed byte slice. Every read hands back a sub-slice of the original buffer.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Reader<'a> {
+        Reader { bytes }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], RNGError> {
+        if self.bytes.len() < n {
+            return Err(FormatError::Truncated.into());
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, RNGError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, RNGError> {
+        let b = self.take(2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, RNGError> {
+        let b = self.take(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn str(&mut self, len: usize) -> Result<&'a str, RNGError> {
+        std::str::from_utf8(self.take(len)?).map_err(|e| FormatError::InvalidUtf8(e).into())
+    }
+
+    fn str16(&mut self) -> Result<&'a str, RNGError> {
+        let len = self.u16()?;
+        self.str(usize::from(len))
+    }
+
+    fn joiners(&mut self) -> Result<(Joiner, Joiner), RNGError> {
+        let packed = self.u8()?;
+        Ok((
+            Joiner::from_bits_truncate(packed >> 4),
+            Joiner::from_bits_truncate(packed & 0x0F),
+        ))
+    }
+
+    fn section(
+        &mut self,
+        classification: &Classification,
+        weighted: bool,
+    ) -> Result<Syllables, RNGError> {
+        let count = self.u32()?;
+        let mut syllables = Syllables::new();
+        for _ in 0..count {
+            let (jprevious, jnext) = self.joiners()?;
+            let len = self.u8()?;
+            let has_weight = weighted && len & WEIGHTED != 0;
+            let len = if weighted { len & !WEIGHTED } else { len };
+            let value = self.str(usize::from(len))?.to_string();
+            let weight = if has_weight { self.u32()? } else { 1 };
+            syllables.add(Syllable {
+                value,
+                classification: classification.clone(),
+                jprevious,
+                jnext,
+                weight,
+            });
+        }
+        Ok(syllables)
+    }
+
+    fn spellings(&mut self) -> Result<Orthography, RNGError> {
+        let count = self.u32()?;
+        let mut orthography = Orthography::new();
+        for _ in 0..count {
+            let script = match self.u8()? {
+                0 => Script::Latin,
+                1 => Script::Cyrillic,
+                script => return Err(FormatError::UnknownScript(script).into()),
+            };
+            let phoneme = self.str16()?;
+            let spelling = self.str16()?;
+            orthography.add(script, phoneme, spelling);
+        }
+        Ok(orthography)
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod compiled_tests {
+    use super::*;
+    use crate::{Language, LoadPolicy};
+    use rstest::rstest;
+    use std::error::Error;
+
+    #[rstest(
+        language,
+        case(Language::Curse),
+        case(Language::Demonic),
+        case(Language::Elven),
+        case(Language::Эльфийский),
+        case(Language::Fantasy),
+        case(Language::Фантазия),
+        case(Language::GermanCurse),
+        case(Language::Goblin),
+        case(Language::Гоблин),
+        case(Language::Klingon),
+        case(Language::Roman),
+        case(Language::Римский)
+    )]
+    fn round_trip(language: Language) {
+        let rng = RNG::try_from(&language).unwrap();
+
+        let loaded = load(&compile(&rng).unwrap()).unwrap();
+
+        assert_eq!(loaded, rng);
+    }
+
+    #[test]
+    fn compile__header() {
+        let rng = RNG::new_from_file("src/languages/Test-micro.txt".to_string()).unwrap();
+
+        let bytes = compile(&rng).unwrap();
+
+        assert!(is_compiled(&bytes));
+        assert_eq!(&bytes[..4], MAGIC);
+        assert_eq!(bytes[4], VERSION);
+    }
+
+    #[test]
+    fn compile__syllable_too_long() {
+        let prefix = "a".repeat(130);
+        let rng = RNG::load(
+            "Long".to_string(),
+            &format!("-{prefix}\n+zu"),
+            LoadPolicy::Lenient,
+        )
+        .unwrap()
+        .0;
+
+        let error = compile(&rng).unwrap_err();
+
+        assert_eq!(error.kind(), RNGErrorKind::WriteError);
+        assert!(
+            error
+                .text()
+                .unwrap()
+                .ends_with("syllables must be shorter than 128 bytes")
+        );
+    }
+
+    #[test]
+    fn compile__spelling_too_long() {
+        let mut rng = RNG::load("Long".to_string(), "-ka\n+zu", LoadPolicy::Lenient)
+            .unwrap()
+            .0;
+        rng.orthography = Orthography::new().with_spelling(Script::Latin, "k", &"c".repeat(70_000));
+
+        assert_eq!(compile(&rng).unwrap_err().kind(), RNGErrorKind::WriteError);
+    }
+
+    #[test]
+    fn round_trip__weights() {
+        let rng = RNG::new_from_str("Weighted".to_string(), "-a *3\nb\n+c -v *200").unwrap();
+
+        let loaded = load(&compile(&rng).unwrap()).unwrap();
+
+        assert_eq!(loaded, rng);
+        assert_eq!(loaded.suffixes.all()[0].weight, 200);
+    }
+
+    #[test]
+    fn load__version_1() {
+        // "Micro" with -a, b and +c, as written before weights were stored.
+        let bytes = [
+            b"RNGC".as_slice(),
+            &[1, 5, 0],
+            b"Micro",
+            &[1, 0, 0, 0, 0x33, 1, b'a'],
+            &[1, 0, 0, 0, 0x11, 1, b'b'],
+            &[1, 0, 0, 0, 0x11, 1, b'c'],
+        ]
+        .concat();
+
+        let rng = load(&bytes).unwrap();
+
+        assert_eq!(
+            rng,
+            RNG::new_from_str("Micro".to_string(), "-a\nb\n+c").unwrap()
+        );
+    }
+
+    #[test]
+    fn round_trip__spellings() {
+        let rng = RNG::new_from_str(
+            "Phonemic".to_string(),
+            "@spell latin ʃ sh\n@spell cyrillic ʃ ш\n-ʃa\nri\n+ŋu",
+        )
+        .unwrap();
+
+        let loaded = load(&compile(&rng).unwrap()).unwrap();
+
+        assert_eq!(loaded, rng);
+        assert_eq!(
+            loaded.orthography.spelling(Script::Cyrillic, "ʃ"),
+            Some("ш")
+        );
+    }
+
+    #[test]
+    fn round_trip__capitalization() {
+        let rng = RNG::new_from_str(
+            "Klingon".to_string(),
+            "@capitalize apostrophe\n-qo'\nno\n+s",
+        )
+        .unwrap();
+
+        let loaded = load(&compile(&rng).unwrap()).unwrap();
+
+        assert_eq!(loaded, rng);
+        assert_eq!(loaded.capitalization, Capitalization::AfterApostrophe);
+    }
+
+    #[test]
+    fn load__unknown_capitalization() {
+        let rng = RNG::new_from_str("Micro".to_string(), "-a\nb\n+c").unwrap();
+        let mut bytes = compile(&rng).unwrap();
+        *bytes.last_mut().unwrap() = 9;
+
+        let error = load(&bytes).unwrap_err();
+
+        assert_eq!(
+            error.source().unwrap().to_string(),
+            "unknown capitalization 9"
+        );
+    }
+
+    #[test]
+    fn load__unknown_script() {
+        let rng = RNG::new_from_str("Micro".to_string(), "@spell latin ʃ sh\n-a\nb\n+c").unwrap();
+        let mut bytes = compile(&rng).unwrap();
+        // The only spelling comes just before the capitalization at the end of the file.
+        let script = bytes.len() - 1 - (2 + "ʃ".len() + 2 + "sh".len()) - 1;
+        bytes[script] = 7;
+
+        let error = load(&bytes).unwrap_err();
+
+        assert_eq!(error.source().unwrap().to_string(), "unknown script 7");
+    }
+
+    #[test]
+    fn load__bad_magic() {
+        let error = load(b"-a\nb\n+c").unwrap_err();
+
+        assert_eq!(error.kind(), RNGErrorKind::InvalidLanguageFile);
+        assert_eq!(
+            error.source().unwrap().to_string(),
+            "missing compiled language header"
+        );
+    }
+
+    #[test]
+    fn load__unsupported_version() {
+        let rng = RNG::try_from(&Language::Goblin).unwrap();
+        let mut bytes = compile(&rng).unwrap();
+        bytes[4] = VERSION + 1;
+
+        let error = load(&bytes).unwrap_err();
+
+        assert_eq!(error.kind(), RNGErrorKind::InvalidLanguageFile);
+        assert_eq!(
+            error.source().unwrap().to_string(),
+            format!("unsupported compiled format version {}", VERSION + 1)
+        );
+    }
+
+    #[test]
+    fn load__truncated() {
+        let rng = RNG::try_from(&Language::Goblin).unwrap();
+        let bytes = compile(&rng).unwrap();
+
+        assert_eq!(
+            load(&bytes[..bytes.len() - 1]).unwrap_err().kind(),
+            RNGErrorKind::InvalidLanguageFile
+        );
+    }
+
+    #[test]
+    fn load__trailing_bytes() {
+        let rng = RNG::try_from(&Language::Goblin).unwrap();
+        let mut bytes = compile(&rng).unwrap();
+        bytes.push(0);
+
+        assert_eq!(
+            load(&bytes).unwrap_err().kind(),
+            RNGErrorKind::InvalidLanguageFile
+        );
+    }
+}
@@ -0,0 +1,608 @@
+use rand::Rng;
+use rand::distr::{Distribution, weighted::WeightedIndex};
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::rng_blocklist::Blocklist;
+use crate::rng_diversity::Diversity;
+use crate::rng_syllable::Syllable;
+use crate::rng_syllables::Syllables;
+use crate::rng_weighted_rnd::NORMAL_WEIGHT;
+use crate::{RNG, RNGError, RNGErrorKind};
+
+/// The most syllables a constrained name will be built from, when the usual two to five aren't
+/// enough to reach the minimum length.
+const MAX_SYLLABLES: usize = 8;
+
+/// How many syllables the search may try before giving up on a set of constraints.
+const SEARCH_BUDGET: usize = 100_000;
+
+/// `NameConstraints` narrows down the names a language generates. Lengths are counted in
+/// characters, and the initial letter and substrings are matched ignoring case. The regular
+/// expression is matched against the finished, capitalized name.
+///
+/// Rather than generating names until one happens to fit, `RNG::generate_name_with` prunes
+/// syllables as it goes: a syllable is only picked if the name can still be finished within the
+/// length limits from there, and the search backs up when a forbidden substring appears.
+///
+/// ```
+/// use rnglib::{Language, NameConstraints, RNG};
+///
+/// let rng = RNG::try_from(&Language::Elven).unwrap();
+/// let constraints = NameConstraints::new().starting_with('S').length(5, 8);
+///
+/// let name = rng.generate_name_with(&constraints).unwrap();
+///
+/// assert!(name.starts_with('S'));
+/// assert!((5..=8).contains(&name.chars().count()));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct NameConstraints {
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    initial: Option<String>,
+    contains: Vec<String>,
+    excludes: Vec<String>,
+    pattern: Option<Regex>,
+    blocklist: Option<Arc<Blocklist>>,
+    diversity: Option<Diversity>,
+}
+
+impl NameConstraints {
+    #[must_use]
+    pub fn new() -> NameConstraints {
+        NameConstraints::default()
+    }
+
+    #[must_use]
+    pub fn min_length(mut self, min: usize) -> NameConstraints {
+        self.min_length = Some(min);
+        self
+    }
+
+    #[must_use]
+    pub fn max_length(mut self, max: usize) -> NameConstraints {
+        self.max_length = Some(max);
+        self
+    }
+
+    /// Sets both the minimum and maximum length, inclusive.
+    #[must_use]
+    pub fn length(self, min: usize, max: usize) -> NameConstraints {
+        self.min_length(min).max_length(max)
+    }
+
+    #[must_use]
+    pub fn starting_with(mut self, initial: char) -> NameConstraints {
+        self.initial = Some(initial.to_lowercase().collect());
+        self
+    }
+
+    /// Requires the name to contain the text. Can be called more than once.
+    #[must_use]
+    pub fn containing(mut self, text: &str) -> NameConstraints {
+        self.contains.push(text.to_lowercase());
+        self
+    }
+
+    /// Forbids the name from containing the text. Can be called more than once.
+    #[must_use]
+    pub fn excluding(mut self, text: &str) -> NameConstraints {
+        self.excludes.push(text.to_lowercase());
+        self
+    }
+
+    #[must_use]
+    pub fn matching(mut self, pattern: Regex) -> NameConstraints {
+        self.pattern = Some(pattern);
+        self
+    }
+
+    /// Rejects names that match the blocklist. Copies of the constraints share the blocklist, and
+    /// its count of rejected names.
+    #[must_use]
+    pub fn with_blocklist(mut self, blocklist: Blocklist) -> NameConstraints {
+        self.blocklist = Some(Arc::new(blocklist));
+        self
+    }
+
+    #[must_use]
+    pub fn blocklist(&self) -> Option<&Blocklist> {
+        self.blocklist.as_deref()
+    }
+
+    /// Keeps names that are too alike out of the same batch, and away from existing names.
+    #[must_use]
+    pub fn with_diversity(mut self, diversity: Diversity) -> NameConstraints {
+        self.diversity = Some(diversity);
+        self
+    }
+
+    #[must_use]
+    pub fn diversity(&self) -> Option<&Diversity> {
+        self.diversity.as_ref()
+    }
+
+    /// True if the name satisfies every constraint.
+    #[must_use]
+    pub fn is_satisfied_by(&self, name: &str) -> bool {
+        self.fits(name)
+            && self
+                .blocklist()
+                .is_none_or(|blocklist| !blocklist.blocks(name))
+    }
+
+    /// True if the name satisfies every constraint other than the blocklist.
+    fn fits(&self, name: &str) -> bool {
+        let lower = name.to_lowercase();
+        let length = name.chars().count();
+        self.min_length.is_none_or(|min| length >= min)
+            && self.max_length.is_none_or(|max| length <= max)
+            && self
+                .initial
+                .as_ref()
+                .is_none_or(|initial| lower.starts_with(initial.as_str()))
+            && self
+                .contains
+                .iter()
+                .all(|text| lower.contains(text.as_str()))
+            && !self
+                .excludes
+                .iter()
+                .any(|text| lower.contains(text.as_str()))
+            && self.pattern.as_ref().is_none_or(|re| re.is_match(name))
+    }
+
+    /// True if a name starting with `partial` (lowercased) has already broken a constraint that
+    /// adding more syllables can't repair.
+    fn rules_out(&self, partial: &str) -> bool {
+        self.excludes
+            .iter()
+            .any(|text| partial.contains(text.as_str()))
+    }
+
+    fn allows_length(&self, min: usize, max: usize) -> bool {
+        self.min_length.is_none_or(|limit| max >= limit)
+            && self.max_length.is_none_or(|limit| min <= limit)
+    }
+}
+
+/// Generates a name made up of syllables that satisfy the constraints, and that is distinct from
+/// the names already in the batch.
+pub(crate) fn generate<R: Rng + ?Sized>(
+    language: &RNG,
+    constraints: &NameConstraints,
+    batch: &[String],
+    rng: &mut R,
+) -> Result<Syllables, RNGError> {
+    let mut search = Search {
+        language,
+        constraints,
+        batch,
+        bounds: HashMap::new(),
+        budget: SEARCH_BUDGET,
+    };
+
+    let first = usize::from(NORMAL_WEIGHT.random_with(rng));
+    let counts = std::iter::once(first).chain((2..=MAX_SYLLABLES).filter(|c| *c != first));
+    for count in counts {
+        if search.budget == 0 {
+            break;
+        }
+        let mut syllables = Vec::with_capacity(count);
+        if search.prefix(count, &mut syllables, rng) {
+            return Ok(syllables.into_iter().cloned().collect());
+        }
+    }
+
+    Err(
+        RNGError::new(RNGErrorKind::GenerationError).with_text(&format!(
+            "no {} name satisfies the constraints",
+            language.name
+        )),
+    )
+}
+
+/// A randomized, depth-first walk through the syllables that can follow one another, cut short
+/// wherever the constraints can no longer be met.
+struct Search<'a> {
+    language: &'a RNG,
+    constraints: &'a NameConstraints,
+    batch: &'a [String],
+    /// Shortest and longest lengths of the syllables that can follow a syllable, keyed by the
+    /// syllable's address and how many syllables follow it.
+    bounds: HashMap<(*const Syllable, usize), Option<(usize, usize)>>,
+    budget: usize,
+}
+
+impl<'a> Search<'a> {
+    fn prefix<R: Rng + ?Sized>(
+        &mut self,
+        count: usize,
+        syllables: &mut Vec<&'a Syllable>,
+        rng: &mut R,
+    ) -> bool {
+        let candidates: Vec<&'a Syllable> = self
+            .language
+            .prefixes
+            .all()
+            .iter()
+            .filter(|p| {
+                self.constraints.initial.as_ref().is_none_or(|initial| {
+                    p.value.to_lowercase().starts_with(initial.as_str())
+                        || initial.starts_with(p.value.to_lowercase().as_str())
+                })
+            })
+            .collect();
+
+        for prefix in weighted_order(candidates, rng) {
+            if !self.spend() {
+                return false;
+            }
+            let partial = prefix.value.to_lowercase();
+            if self.is_viable(prefix, &partial, count - 1) {
+                syllables.push(prefix);
+                if self.next(&partial, count - 1, syllables, rng) {
+                    return true;
+                }
+                syllables.pop();
+            }
+        }
+        false
+    }
+
+    /// Adds the `remaining` syllables that follow the last one in `syllables`.
+    fn next<R: Rng + ?Sized>(
+        &mut self,
+        partial: &str,
+        remaining: usize,
+        syllables: &mut Vec<&'a Syllable>,
+        rng: &mut R,
+    ) -> bool {
+        let Some(last) = syllables.last().copied() else {
+            return false;
+        };
+        let pool = if remaining == 1 {
+            &self.language.suffixes
+        } else {
+            &self.language.centers
+        };
+        let candidates: Vec<&'a Syllable> =
+            pool.all().iter().filter(|s| last.connects(s)).collect();
+
+        for syllable in weighted_order(candidates, rng) {
+            if !self.spend() {
+                return false;
+            }
+            let extended = format!("{partial}{}", syllable.value.to_lowercase());
+            if remaining == 1 {
+                syllables.push(syllable);
+                let name = self
+                    .language
+                    .finish_name(&syllables.iter().copied().cloned().collect());
+                if self.constraints.fits(&name)
+                    && self
+                        .constraints
+                        .diversity()
+                        .is_none_or(|diversity| diversity.is_distinct(&name, self.batch))
+                    && self
+                        .constraints
+                        .blocklist()
+                        .is_none_or(|blocklist| !blocklist.rejects(&name))
+                {
+                    return true;
+                }
+                syllables.pop();
+            } else if self.is_viable(syllable, &extended, remaining - 1) {
+                syllables.push(syllable);
+                if self.next(&extended, remaining - 1, syllables, rng) {
+                    return true;
+                }
+                syllables.pop();
+            }
+        }
+        false
+    }
+
+    /// True if a name starting with `partial`, which ends in `last`, can still be finished with
+    /// `remaining` more syllables.
+    fn is_viable(&mut self, last: &Syllable, partial: &str, remaining: usize) -> bool {
+        if self.constraints.rules_out(partial)
+            || self
+                .constraints
+                .blocklist()
+                .is_some_and(|blocklist| blocklist.blocks_within(partial))
+        {
+            return false;
+        }
+        let length = partial.chars().count();
+        match self.bounds(last, remaining) {
+            Some((min, max)) => self.constraints.allows_length(length + min, length + max),
+            None => false,
+        }
+    }
+
+    /// The shortest and longest total length of `remaining` syllables that can follow `from`, or
+    /// `None` if no syllables can.
+    fn bounds(&mut self, from: &Syllable, remaining: usize) -> Option<(usize, usize)> {
+        let key = (std::ptr::from_ref(from), remaining);
+        if let Some(bounds) = self.bounds.get(&key) {
+            return *bounds;
+        }
+
+        let language = self.language;
+        let bounds = if remaining == 1 {
+            language
+                .suffixes
+                .all()
+                .iter()
+                .filter(|s| from.connects(s))
+                .map(|s| s.value.chars().count())
+                .fold(None, |bounds, length| Some(widen(bounds, length, length)))
+        } else {
+            let mut bounds = None;
+            for center in language.centers.all().iter().filter(|c| from.connects(c)) {
+                if let Some((min, max)) = self.bounds(center, remaining - 1) {
+                    let length = center.value.chars().count();
+                    bounds = Some(widen(bounds, length + min, length + max));
+                }
+            }
+            bounds
+        };
+        self.bounds.insert(key, bounds);
+        bounds
+    }
+
+    fn spend(&mut self) -> bool {
+        self.budget = self.budget.saturating_sub(1);
+        self.budget > 0
+    }
+}
+
+fn widen(bounds: Option<(usize, usize)>, min: usize, max: usize) -> (usize, usize) {
+    match bounds {
+        Some((lo, hi)) => (lo.min(min), hi.max(max)),
+        None => (min, max),
+    }
+}
+
+/// Puts the syllables in a random order, each drawn in proportion to its weight from those not
+/// yet drawn.
+fn weighted_order<'a, R: Rng + ?Sized>(
+    mut syllables: Vec<&'a Syllable>,
+    rng: &mut R,
+) -> Vec<&'a Syllable> {
+    let mut order = Vec::with_capacity(syllables.len());
+    // Summed as u64, like Syllables::rnd, so that weights added up past u32::MAX still work.
+    while let Ok(index) = WeightedIndex::new(syllables.iter().map(|s| u64::from(s.weight))) {
+        order.push(syllables.swap_remove(index.sample(rng)));
+    }
+    order
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod constraints_tests {
+    use super::*;
+    use crate::rng_blocklist::BlockMode;
+    use crate::{Language, LoadPolicy};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use rstest::rstest;
+    use std::str::FromStr;
+
+    #[test]
+    fn is_satisfied_by() {
+        let constraints = NameConstraints::new()
+            .length(5, 8)
+            .starting_with('t')
+            .containing("AN")
+            .excluding("x");
+
+        assert!(constraints.is_satisfied_by("Tandor"));
+        assert!(!constraints.is_satisfied_by("Tan"));
+        assert!(!constraints.is_satisfied_by("Tandorinel"));
+        assert!(!constraints.is_satisfied_by("Randor"));
+        assert!(!constraints.is_satisfied_by("Tendor"));
+        assert!(!constraints.is_satisfied_by("Tanxor"));
+    }
+
+    #[test]
+    fn is_satisfied_by__pattern() {
+        let constraints = NameConstraints::new().matching(Regex::new("^[A-Z][a-z]+on$").unwrap());
+
+        assert!(constraints.is_satisfied_by("Thalion"));
+        assert!(!constraints.is_satisfied_by("Thalia"));
+    }
+
+    #[test]
+    fn is_satisfied_by__blocklist() {
+        let constraints = NameConstraints::new()
+            .with_blocklist(Blocklist::new().with_word("thal", BlockMode::Substring));
+
+        assert!(!constraints.is_satisfied_by("Thalion"));
+        assert!(constraints.is_satisfied_by("Aelion"));
+        assert_eq!(constraints.blocklist().unwrap().rejected(), 0);
+    }
+
+    #[test]
+    fn is_satisfied_by__nothing_required() {
+        assert!(NameConstraints::new().is_satisfied_by("X"));
+    }
+
+    #[rstest]
+    #[case(Language::Elven)]
+    #[case(Language::Goblin)]
+    #[case(Language::Roman)]
+    #[case(Language::Эльфийский)]
+    fn generate__length_and_initial(#[case] language: Language) {
+        let rng = RNG::try_from(&language).unwrap();
+        let initial = rng.prefixes.all()[0].value.chars().next().unwrap();
+        let constraints = NameConstraints::new().starting_with(initial).length(5, 8);
+        let mut random = StdRng::seed_from_u64(1);
+
+        for _ in 0..50 {
+            let name = generate(&rng, &constraints, &[], &mut random).unwrap();
+            let name = rng.finish_name(&name);
+
+            assert!(constraints.is_satisfied_by(&name), "{name}");
+        }
+    }
+
+    #[test]
+    fn generate__long_names_use_more_syllables() {
+        let rng = RNG::new_from_str("Micro".to_string(), "-ka\nri\n+zu").unwrap();
+        let constraints = NameConstraints::new().min_length(14);
+
+        let syllables = generate(&rng, &constraints, &[], &mut rand::rng()).unwrap();
+
+        assert!(syllables.collapse().chars().count() >= 14);
+        assert!(syllables.len() <= MAX_SYLLABLES);
+    }
+
+    #[test]
+    fn generate__contains_and_excludes() {
+        let rng = RNG::try_from(&Language::Goblin).unwrap();
+        let constraints = NameConstraints::new().containing("zu").excluding("ag");
+        let mut random = StdRng::seed_from_u64(2);
+
+        for _ in 0..20 {
+            let name = rng.finish_name(&generate(&rng, &constraints, &[], &mut random).unwrap());
+
+            assert!(name.to_lowercase().contains("zu"), "{name}");
+            assert!(!name.to_lowercase().contains("ag"), "{name}");
+        }
+    }
+
+    #[test]
+    fn generate__blocklist_counts_rejections() {
+        let rng = RNG::load(
+            "Micro".to_string(),
+            "-ka\n-ri\n+zu\n+do",
+            LoadPolicy::Lenient,
+        )
+        .unwrap()
+        .0;
+        let constraints = NameConstraints::new().with_blocklist(
+            Blocklist::new()
+                .with_word("kazu", BlockMode::Exact)
+                .with_word("r1", BlockMode::Normalized),
+        );
+        let copy = constraints.clone();
+
+        for _ in 0..20 {
+            let name = rng.finish_name(&generate(&rng, &copy, &[], &mut rand::rng()).unwrap());
+
+            assert_eq!(name, "Kado");
+        }
+        assert!(constraints.blocklist().unwrap().rejected() > 0);
+    }
+
+    #[test]
+    fn generate__pruned_starts_are_not_rejections() {
+        let rng = RNG::load(
+            "Micro".to_string(),
+            "-ka\n-ri\n+zu\n+do",
+            LoadPolicy::Lenient,
+        )
+        .unwrap()
+        .0;
+        let constraints = NameConstraints::new()
+            .with_blocklist(Blocklist::new().with_word("ka", BlockMode::Substring));
+
+        for _ in 0..20 {
+            generate(&rng, &constraints, &[], &mut rand::rng()).unwrap();
+        }
+
+        assert_eq!(constraints.blocklist().unwrap().rejected(), 0);
+    }
+
+    #[test]
+    fn generate__distinct_from_batch() {
+        let rng = RNG::load(
+            "Micro".to_string(),
+            "-ka\n-ri\n+zu\n+do",
+            LoadPolicy::Lenient,
+        )
+        .unwrap()
+        .0;
+        let constraints = NameConstraints::new().with_diversity(Diversity::new());
+        let batch = vec!["Kazu".to_string(), "Kado".to_string(), "Rizu".to_string()];
+
+        let name =
+            rng.finish_name(&generate(&rng, &constraints, &batch, &mut rand::rng()).unwrap());
+
+        assert_eq!(name, "Rido");
+        assert!(
+            generate(
+                &rng,
+                &constraints,
+                &[batch, vec!["Rido".to_string()]].concat(),
+                &mut rand::rng()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn generate__pattern() {
+        let rng = RNG::try_from(&Language::Roman).unwrap();
+        let constraints = NameConstraints::new().matching(Regex::new("us$").unwrap());
+
+        let name = rng.finish_name(&generate(&rng, &constraints, &[], &mut rand::rng()).unwrap());
+
+        assert!(name.ends_with("us"), "{name}");
+    }
+
+    #[test]
+    fn generate__unsatisfiable() {
+        let rng = RNG::try_from(&Language::Elven).unwrap();
+        let constraints = NameConstraints::new().length(3, 2);
+
+        let error = generate(&rng, &constraints, &[], &mut rand::rng()).unwrap_err();
+
+        assert_eq!(error.kind(), RNGErrorKind::GenerationError);
+        assert_eq!(
+            error.text(),
+            Some("no Elven name satisfies the constraints")
+        );
+    }
+
+    #[test]
+    fn generate__unknown_initial() {
+        let rng = RNG::try_from(&Language::Elven).unwrap();
+        let constraints = NameConstraints::new().starting_with('ж');
+
+        assert!(generate(&rng, &constraints, &[], &mut rand::rng()).is_err());
+    }
+
+    #[test]
+    fn bounds() {
+        let rng = RNG::new_from_str("Micro".to_string(), "-ka\nri\nmor\n+zu\n+dan").unwrap();
+        let constraints = NameConstraints::new();
+        let mut search = Search {
+            language: &rng,
+            constraints: &constraints,
+            batch: &[],
+            bounds: HashMap::new(),
+            budget: SEARCH_BUDGET,
+        };
+        let prefix = &rng.prefixes.all()[0];
+
+        assert_eq!(search.bounds(prefix, 1), Some((2, 3)));
+        assert_eq!(search.bounds(prefix, 3), Some((6, 9)));
+    }
+
+    #[test]
+    fn weighted_order__weights_past_u32_max() {
+        let syllables: Vec<Syllable> = ["a *4294967295", "b *4294967295", "c"]
+            .iter()
+            .map(|s| Syllable::from_str(s).unwrap())
+            .collect();
+
+        let order = weighted_order(syllables.iter().collect(), &mut StdRng::seed_from_u64(1));
+
+        assert_eq!(order.len(), 3);
+    }
+}
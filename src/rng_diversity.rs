@@ -0,0 +1,246 @@
+use std::path::Path;
+
+use crate::RNGError;
+
+/// `Diversity` keeps a batch of names from containing names that are too alike, such as
+/// "Aelor" and "Aelorn". A name is rejected if it is within the edit distance of a name already
+/// in the batch, or of one of the existing names it has been given, and, when phonetic matching
+/// is on, if it sounds like one of them.
+///
+/// By default only exact duplicates, ignoring case, are rejected.
+///
+/// ```
+/// use rnglib::{Diversity, Language, NameConstraints, RNG};
+///
+/// let rng = RNG::try_from(&Language::Elven).unwrap();
+/// let constraints = NameConstraints::new()
+///     .with_diversity(Diversity::new().within(2).phonetic().with_existing(["Aelor"]));
+///
+/// let names = rng.generate_names_with(5, &constraints).unwrap();
+///
+/// assert_eq!(names.len(), 5);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Diversity {
+    distance: usize,
+    phonetic: bool,
+    existing: Vec<(String, String)>,
+}
+
+impl Diversity {
+    #[must_use]
+    pub fn new() -> Diversity {
+        Diversity::default()
+    }
+
+    /// Rejects names within `distance` edits (insertions, deletions or substitutions) of another
+    /// name.
+    #[must_use]
+    pub fn within(mut self, distance: usize) -> Diversity {
+        self.distance = distance;
+        self
+    }
+
+    /// Rejects names that sound like another name, going by a Soundex-style phonetic key.
+    #[must_use]
+    pub fn phonetic(mut self) -> Diversity {
+        self.phonetic = true;
+        self
+    }
+
+    /// Adds names, such as those already in use in a campaign, that new names must differ from.
+    #[must_use]
+    pub fn with_existing<S: AsRef<str>>(mut self, names: impl IntoIterator<Item = S>) -> Diversity {
+        for name in names {
+            let name = name.as_ref().trim();
+            if !name.is_empty() {
+                self.existing
+                    .push((name.to_lowercase(), phonetic_key(name)));
+            }
+        }
+        self
+    }
+
+    /// Adds the names in a file, one per line.
+    ///
+    /// # Errors
+    ///
+    /// Errors out with `RNGErrorKind::ReadError` if the file can't be read.
+    pub fn with_existing_file(self, path: impl AsRef<Path>) -> Result<Diversity, RNGError> {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(names) => Ok(self.with_existing(names.lines())),
+            Err(e) => Err(RNGError::from(e).with_file(&path.to_string_lossy())),
+        }
+    }
+
+    /// True if the name is far enough from the names in the batch and the existing names.
+    #[must_use]
+    pub fn is_distinct(&self, name: &str, batch: &[String]) -> bool {
+        let lower = name.to_lowercase();
+        let key = if self.phonetic {
+            phonetic_key(name)
+        } else {
+            String::new()
+        };
+        let too_close = |other: &str, other_key: &str| {
+            levenshtein(&lower, other) <= self.distance || (self.phonetic && key == other_key)
+        };
+
+        !self
+            .existing
+            .iter()
+            .any(|(other, other_key)| too_close(other, other_key))
+            && !batch.iter().any(|other| {
+                let other_key = if self.phonetic {
+                    phonetic_key(other)
+                } else {
+                    String::new()
+                };
+                too_close(&other.to_lowercase(), &other_key)
+            })
+    }
+}
+
+/// The number of single character insertions, deletions and substitutions that turn one string
+/// into the other.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// A Soundex-style key: the first letter, followed by up to three digits for the consonant
+/// sounds that follow it, so that names that sound alike share a key. Cyrillic consonants are
+/// grouped the same way as their Latin counterparts.
+fn phonetic_key(name: &str) -> String {
+    let mut letters = name
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| c.is_alphabetic());
+    let Some(first) = letters.next() else {
+        return String::new();
+    };
+
+    let mut key: String = first.to_uppercase().collect();
+    let mut last = sound(first);
+    for c in letters {
+        let code = sound(c);
+        if code != '0' && code != last {
+            key.push(code);
+            if key.chars().count() == 4 {
+                break;
+            }
+        }
+        // Like Soundex, a vowel separates two consonants with the same code, but h and w don't.
+        if !matches!(c, 'h' | 'w') {
+            last = code;
+        }
+    }
+    while key.chars().count() < 4 {
+        key.push('0');
+    }
+    key
+}
+
+fn sound(c: char) -> char {
+    match c {
+        'b' | 'f' | 'p' | 'v' | 'б' | 'в' | 'п' | 'ф' => '1',
+        'c' | 'g' | 'j' | 'k' | 'q' | 's' | 'x' | 'z' | 'г' | 'ж' | 'з' | 'к' | 'с' | 'х' | 'ц'
+        | 'ч' | 'ш' | 'щ' => '2',
+        'd' | 't' | 'д' | 'т' => '3',
+        'l' | 'л' => '4',
+        'm' | 'n' | 'м' | 'н' => '5',
+        'r' | 'р' => '6',
+        _ => '0',
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod diversity_tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", "", 0)]
+    #[case("aelor", "aelor", 0)]
+    #[case("aelor", "aelorn", 1)]
+    #[case("kitten", "sitting", 3)]
+    #[case("", "abc", 3)]
+    #[case("гоблин", "гоблен", 1)]
+    fn levenshtein__cases(#[case] a: &str, #[case] b: &str, #[case] expected: usize) {
+        assert_eq!(levenshtein(a, b), expected);
+        assert_eq!(levenshtein(b, a), expected);
+    }
+
+    #[rstest]
+    #[case("Robert", "R163")]
+    #[case("Rupert", "R163")]
+    #[case("Ashcraft", "A261")]
+    #[case("Tymczak", "T522")]
+    #[case("Pfister", "P236")]
+    #[case("Lee", "L000")]
+    #[case("Аэлор", "А460")]
+    #[case("", "")]
+    fn phonetic_key__cases(#[case] name: &str, #[case] expected: &str) {
+        assert_eq!(phonetic_key(name), expected);
+    }
+
+    #[test]
+    fn is_distinct__duplicates_by_default() {
+        let diversity = Diversity::new();
+        let batch = vec!["Aelor".to_string()];
+
+        assert!(!diversity.is_distinct("AELOR", &batch));
+        assert!(diversity.is_distinct("Aelorn", &batch));
+    }
+
+    #[test]
+    fn is_distinct__within() {
+        let diversity = Diversity::new().within(1);
+        let batch = vec!["Aelor".to_string()];
+
+        assert!(!diversity.is_distinct("Aelorn", &batch));
+        assert!(diversity.is_distinct("Aelorian", &batch));
+    }
+
+    #[test]
+    fn is_distinct__phonetic() {
+        let diversity = Diversity::new().phonetic();
+        let batch = vec!["Robert".to_string()];
+
+        assert!(!diversity.is_distinct("Rupert", &batch));
+        assert!(diversity.is_distinct("Rubella", &batch));
+    }
+
+    #[test]
+    fn is_distinct__existing() {
+        let diversity = Diversity::new()
+            .within(1)
+            .with_existing(["Aelor", " ", "Thalion"]);
+
+        assert_eq!(diversity.existing.len(), 2);
+        assert!(!diversity.is_distinct("Thalian", &[]));
+        assert!(diversity.is_distinct("Gimli", &[]));
+    }
+
+    #[test]
+    fn with_existing_file__missing() {
+        let error = Diversity::new()
+            .with_existing_file("src/languages/none.txt")
+            .unwrap_err();
+
+        assert_eq!(error.kind(), crate::RNGErrorKind::ReadError);
+    }
+}
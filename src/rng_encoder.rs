@@ -0,0 +1,268 @@
+use crate::rng_enumerate::{NameSpace, distinct};
+use crate::rng_pseudonym;
+use crate::rng_syllable::Syllable;
+use crate::rng_syllables::Syllables;
+use crate::{Capitalization, RNG, RNGError, RNGErrorKind};
+
+/// `NameEncoder` turns numbers, such as database IDs, into pronounceable names in a language and
+/// back again, like proquints with an accent. `decode` is the exact inverse of `encode`.
+///
+/// Numbers are handed the names the language can make in order, shortest first, so small
+/// numbers get two syllable names and the syllable count grows as the numbers do.
+///
+/// To make sure every name can be read back as a single number, the encoder leaves out any
+/// syllable that would make a name ambiguous: a prefix that begins with another prefix, or a
+/// center or suffix that begins with another center or suffix, such as `ael` when `a` is
+/// already in use.
+///
+/// ```
+/// use rnglib::{Language, RNG};
+///
+/// let rng = RNG::try_from(&Language::Goblin).unwrap();
+/// let encoder = rng.encoder().unwrap();
+///
+/// let name = encoder.encode(1_234_567);
+///
+/// assert_eq!(encoder.decode(&name), Some(1_234_567));
+/// ```
+pub struct NameEncoder<'a> {
+    space: NameSpace<'a>,
+    capitalization: Capitalization,
+    /// How many names there are with fewer syllables than each syllable count, starting at two.
+    offsets: Vec<u128>,
+}
+
+impl<'a> NameEncoder<'a> {
+    pub(crate) fn new(language: &'a RNG) -> Result<NameEncoder<'a>, RNGError> {
+        let mut values = Vec::new();
+        let prefixes = unambiguous(&mut values, distinct(language.prefixes.all()));
+        values.clear();
+        let centers = unambiguous(&mut values, distinct(language.centers.all()));
+        let suffixes = unambiguous(&mut values, distinct(language.suffixes.all()));
+
+        let mut space = NameSpace::from_pools(prefixes, centers, suffixes, 2);
+        let mut offsets = vec![0u128];
+        loop {
+            let names =
+                offsets[offsets.len() - 1].saturating_add(space.count(space.max_syllables()));
+            if names > u128::from(u64::MAX) {
+                break;
+            }
+            if space.max_syllables() == u8::MAX {
+                return Err(
+                    RNGError::new(RNGErrorKind::GenerationError).with_text(&format!(
+                        "{} can't make enough names to encode every number",
+                        language.name
+                    )),
+                );
+            }
+            offsets.push(names);
+            space.grow();
+        }
+        Ok(NameEncoder {
+            space,
+            capitalization: language.capitalization,
+            offsets,
+        })
+    }
+
+    /// The name for the number.
+    ///
+    /// # Panics
+    ///
+    /// Never panics: the encoder is only created if it has a name for every number.
+    #[must_use]
+    pub fn encode(&self, number: u64) -> String {
+        let index = u128::from(number);
+        let position = self.offsets.partition_point(|offset| *offset <= index) - 1;
+        let count = u8::try_from(position + 2).expect("syllable counts fit in a u8");
+        let syllables = self
+            .space
+            .unrank(count, index - self.offsets[position])
+            .expect("the encoder has a name for every number");
+        let syllables: Syllables = syllables.into_iter().cloned().collect();
+        self.capitalization.apply(&syllables.collapse())
+    }
+
+    /// A stable pseudonym for the input, like `RNG::pseudonym` but unique in practice. The input
+    /// is hashed with the secret key (HMAC-SHA256) and the first 64 bits of the hash are
+    /// encoded, so two inputs are only given the same pseudonym if those bits are the same: one
+    /// chance in 2^64 for a pair of inputs, and likely to happen once only after around four
+    /// billion inputs.
+    ///
+    /// ```
+    /// use rnglib::{Language, RNG};
+    ///
+    /// let rng = RNG::try_from(&Language::Goblin).unwrap();
+    /// let encoder = rng.encoder().unwrap();
+    ///
+    /// let name = encoder.pseudonym(b"secret", "player-1234");
+    ///
+    /// assert_eq!(name, encoder.pseudonym(b"secret", "player-1234"));
+    /// ```
+    #[must_use]
+    pub fn pseudonym(&self, key: &[u8], input: &str) -> String {
+        self.encode(rng_pseudonym::number(key, input))
+    }
+
+    /// The number for a name, ignoring case, or `None` if the encoder couldn't have made it.
+    #[must_use]
+    pub fn decode(&self, name: &str) -> Option<u64> {
+        let syllables = self.parse(&name.to_lowercase())?;
+        let offset = self.offsets.get(syllables.len().checked_sub(2)?)?;
+        let index = offset + self.space.rank(&syllables)?;
+        u64::try_from(index).ok()
+    }
+
+    /// Splits the name into syllables. Since no syllable begins with another one that could
+    /// take its place, there is at most one way to do it.
+    fn parse(&self, mut name: &str) -> Option<Vec<&'a Syllable>> {
+        let prefix = starting(&self.space.prefixes, name)?;
+        name = &name[prefix.value.to_lowercase().len()..];
+        let mut syllables = vec![prefix];
+        while !name.is_empty() {
+            let (syllable, is_suffix) = match starting(&self.space.centers, name) {
+                Some(center) => (center, false),
+                None => (starting(&self.space.suffixes, name)?, true),
+            };
+            name = &name[syllable.value.to_lowercase().len()..];
+            syllables.push(syllable);
+            if is_suffix {
+                return name.is_empty().then_some(syllables);
+            }
+        }
+        None
+    }
+}
+
+/// The syllable that `name` starts with, if any.
+fn starting<'a>(syllables: &[&'a Syllable], name: &str) -> Option<&'a Syllable> {
+    syllables
+        .iter()
+        .find(|s| name.starts_with(s.value.to_lowercase().as_str()))
+        .copied()
+}
+
+/// Keeps the syllables that neither begin with, nor are the beginning of, one of the `values`
+/// already kept, ignoring case. Kept syllables are added to the `values`.
+fn unambiguous<'a>(values: &mut Vec<String>, syllables: Vec<&'a Syllable>) -> Vec<&'a Syllable> {
+    syllables
+        .into_iter()
+        .filter(|syllable| {
+            let value = syllable.value.to_lowercase();
+            let clashes = values
+                .iter()
+                .any(|kept| kept.starts_with(value.as_str()) || value.starts_with(kept.as_str()));
+            if !clashes {
+                values.push(value);
+            }
+            !clashes
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod encoder_tests {
+    use super::*;
+    use crate::{Language, LoadPolicy};
+    use proptest::prelude::*;
+    use rstest::rstest;
+    use std::collections::HashSet;
+    use std::str::FromStr;
+
+    #[test]
+    fn pseudonym__unique() {
+        let rng = RNG::try_from(&Language::Goblin).unwrap();
+        let encoder = rng.encoder().unwrap();
+
+        let names: HashSet<String> = (0..10_000)
+            .map(|i| encoder.pseudonym(b"s3cret", &format!("player-{i}")))
+            .collect();
+
+        assert_eq!(names.len(), 10_000);
+    }
+
+    #[test]
+    fn encode() {
+        let rng = RNG::try_from(&Language::Goblin).unwrap();
+        let encoder = rng.encoder().unwrap();
+
+        assert_eq!(encoder.encode(0), encoder.encode(0));
+        assert_ne!(encoder.encode(0), encoder.encode(1));
+        assert!(encoder.encode(u64::MAX).len() > encoder.encode(0).len());
+    }
+
+    #[rstest]
+    #[case(Language::Elven)]
+    #[case(Language::Fantasy)]
+    #[case(Language::Goblin)]
+    #[case(Language::Roman)]
+    #[case(Language::Гоблин)]
+    fn decode__round_trip(#[case] language: Language) {
+        let rng = RNG::try_from(&language).unwrap();
+        let encoder = rng.encoder().unwrap();
+
+        for number in [0, 1, 2, 1000, 65_535, 1 << 40, u64::MAX - 1, u64::MAX] {
+            let name = encoder.encode(number);
+
+            assert_eq!(encoder.decode(&name), Some(number), "{name}");
+            assert_eq!(encoder.decode(&name.to_uppercase()), Some(number), "{name}");
+        }
+    }
+
+    #[test]
+    fn decode__not_a_name() {
+        let rng = RNG::try_from(&Language::Goblin).unwrap();
+        let encoder = rng.encoder().unwrap();
+        let name = encoder.encode(42);
+
+        assert_eq!(encoder.decode(""), None);
+        assert_eq!(encoder.decode("Xyzzy"), None);
+        assert_eq!(encoder.decode(&format!("{name}q")), None);
+    }
+
+    #[test]
+    fn unambiguous__drops_overlapping_syllables() {
+        let syllables = [
+            Syllable::from_str("a").unwrap(),
+            Syllable::from_str("ael").unwrap(),
+            Syllable::from_str("b").unwrap(),
+            Syllable::from_str("B").unwrap(),
+            Syllable::from_str("cor").unwrap(),
+            Syllable::from_str("co").unwrap(),
+        ];
+
+        let kept: Vec<&str> = unambiguous(&mut Vec::new(), syllables.iter().collect())
+            .iter()
+            .map(|s| s.value.as_str())
+            .collect();
+
+        assert_eq!(kept, vec!["a", "b", "cor"]);
+    }
+
+    #[test]
+    fn new__too_few_names() {
+        let rng = RNG::load("Micro".to_string(), "-ka\n+zu", LoadPolicy::Lenient)
+            .unwrap()
+            .0;
+
+        let error = rng.encoder().err().unwrap();
+
+        assert_eq!(error.kind(), RNGErrorKind::GenerationError);
+        assert_eq!(
+            error.text(),
+            Some("Micro can't make enough names to encode every number")
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn decode__inverts_encode(number: u64) {
+            let rng = RNG::try_from(&Language::Elven).unwrap();
+            let encoder = rng.encoder().unwrap();
+
+            prop_assert_eq!(encoder.decode(&encoder.encode(number)), Some(number));
+        }
+    }
+}
@@ -0,0 +1,511 @@
+use rand::Rng;
+use std::ops::RangeInclusive;
+
+use crate::rng_joiner::Joiner;
+use crate::rng_syllable::Syllable;
+use crate::rng_syllables::Syllables;
+use crate::{Capitalization, RNG};
+
+/// The names a language can make from a given number of syllables, ranked in a fixed order:
+/// by prefix, then by each center in turn, then by suffix, each in the order the syllables
+/// appear in the language. Only syllables that join up with the one before them are counted.
+///
+/// Syllables that appear more than once in a language, with the same joiners, are only counted
+/// once.
+pub(crate) struct NameSpace<'a> {
+    pub(crate) prefixes: Vec<&'a Syllable>,
+    pub(crate) centers: Vec<&'a Syllable>,
+    pub(crate) suffixes: Vec<&'a Syllable>,
+    /// `tails[r][j]` is how many ways there are to add `r` more syllables after one whose next
+    /// joiner has the bits `j`.
+    tails: Vec<[u128; 16]>,
+}
+
+impl<'a> NameSpace<'a> {
+    /// Ranks the names of up to `max_syllables` syllables.
+    pub(crate) fn new(language: &'a RNG, max_syllables: u8) -> NameSpace<'a> {
+        NameSpace::from_pools(
+            distinct(language.prefixes.all()),
+            distinct(language.centers.all()),
+            distinct(language.suffixes.all()),
+            max_syllables,
+        )
+    }
+
+    pub(crate) fn from_pools(
+        prefixes: Vec<&'a Syllable>,
+        centers: Vec<&'a Syllable>,
+        suffixes: Vec<&'a Syllable>,
+        max_syllables: u8,
+    ) -> NameSpace<'a> {
+        let mut space = NameSpace {
+            prefixes,
+            centers,
+            suffixes,
+            tails: vec![[1; 16]],
+        };
+        while space.max_syllables() < max_syllables.max(2) {
+            space.grow();
+        }
+        space
+    }
+
+    /// The most syllables the names being ranked can have.
+    pub(crate) fn max_syllables(&self) -> u8 {
+        u8::try_from(self.tails.len()).unwrap_or(u8::MAX)
+    }
+
+    /// Ranks the names with one more syllable than before.
+    pub(crate) fn grow(&mut self) {
+        let remaining = self.tails.len();
+        let mut row = [0; 16];
+        for (bits, count) in (0u8..).zip(row.iter_mut()) {
+            let from = Joiner::from_bits_retain(bits);
+            *count = self
+                .pool(remaining)
+                .iter()
+                .filter(|s| from.joins(s.jprevious))
+                .fold(0u128, |sum, s| {
+                    sum.saturating_add(self.tails[remaining - 1][usize::from(s.jnext.bits())])
+                });
+        }
+        self.tails.push(row);
+    }
+
+    /// The syllables that can be added when `remaining` syllables, including this one, are left.
+    fn pool(&self, remaining: usize) -> &[&'a Syllable] {
+        if remaining == 1 {
+            &self.suffixes
+        } else {
+            &self.centers
+        }
+    }
+
+    /// How many names there are with exactly `syllables` syllables.
+    pub(crate) fn count(&self, syllables: u8) -> u128 {
+        let remaining = usize::from(syllables);
+        if remaining < 2 || remaining > self.tails.len() {
+            return 0;
+        }
+        self.prefixes.iter().fold(0u128, |sum, p| {
+            sum.saturating_add(self.tail(p, remaining - 1))
+        })
+    }
+
+    fn tail(&self, syllable: &Syllable, remaining: usize) -> u128 {
+        self.tails[remaining][usize::from(syllable.jnext.bits())]
+    }
+
+    /// The name with `syllables` syllables at `index` in the ranking, or `None` if there aren't
+    /// that many names.
+    pub(crate) fn unrank(&self, syllables: u8, mut index: u128) -> Option<Vec<&'a Syllable>> {
+        let count = usize::from(syllables);
+        if count < 2 || count > self.tails.len() {
+            return None;
+        }
+
+        let mut name: Vec<&'a Syllable> = Vec::with_capacity(count);
+        for position in 0..count {
+            let remaining = count - position - 1;
+            let pool = if position == 0 {
+                &self.prefixes
+            } else {
+                self.pool(remaining + 1)
+            };
+            let mut chosen = None;
+            for syllable in pool
+                .iter()
+                .filter(|s| name.last().is_none_or(|last| last.connects(s)))
+            {
+                let names = self.tail(syllable, remaining);
+                if index < names {
+                    chosen = Some(*syllable);
+                    break;
+                }
+                index -= names;
+            }
+            name.push(chosen?);
+        }
+        Some(name)
+    }
+
+    /// Where the name, made up of syllables from this name space, is in the ranking, or `None`
+    /// if its syllables don't join up.
+    pub(crate) fn rank(&self, name: &[&'a Syllable]) -> Option<u128> {
+        let count = name.len();
+        if count < 2 || count > self.tails.len() {
+            return None;
+        }
+
+        let mut index = 0u128;
+        for (position, syllable) in name.iter().enumerate() {
+            let remaining = count - position - 1;
+            let pool = if position == 0 {
+                &self.prefixes
+            } else {
+                self.pool(remaining + 1)
+            };
+            let previous = position.checked_sub(1).map(|p| name[p]);
+            let mut found = false;
+            for candidate in pool
+                .iter()
+                .filter(|s| previous.is_none_or(|last| last.connects(s)))
+            {
+                if std::ptr::eq(*candidate, *syllable) {
+                    found = true;
+                    break;
+                }
+                index = index.saturating_add(self.tail(candidate, remaining));
+            }
+            if !found {
+                return None;
+            }
+        }
+        Some(index)
+    }
+}
+
+/// The syllables without exact repeats, keeping the first of each.
+pub(crate) fn distinct(syllables: &[Syllable]) -> Vec<&Syllable> {
+    let mut unique: Vec<&Syllable> = Vec::with_capacity(syllables.len());
+    for syllable in syllables {
+        if !unique.iter().any(|u| {
+            u.value == syllable.value
+                && u.jprevious == syllable.jprevious
+                && u.jnext == syllable.jnext
+        }) {
+            unique.push(syllable);
+        }
+    }
+    unique
+}
+
+/// `NameEnumerator` walks through every name a language can make, for a range of syllable
+/// counts, created with `RNG::enumerate_names`.
+///
+/// Names come out in a fixed order: shorter names first, then by the position of their
+/// syllables in the language. A shuffled enumerator hands them out in a random order instead,
+/// each name exactly once, without having to hold on to the names it has already given out.
+///
+/// ```
+/// use rnglib::RNG;
+///
+/// let rng = RNG::new_from_str("Micro".to_string(), "-ka\n-ri\nmo\n+zu").unwrap();
+///
+/// let names: Vec<String> = rng.enumerate_names(2..=3).collect();
+/// assert_eq!(names, vec!["Kazu", "Rizu", "Kamozu", "Rimozu"]);
+///
+/// let shuffled = rng.enumerate_names(2..=3).shuffled(&mut rand::rng());
+/// assert_eq!(shuffled.count(), 4);
+/// ```
+///
+/// Two different syllable sequences can spell out the same name, such as `ka` + `mo` and `kam`
+/// + `o`; the enumerator treats them as different names.
+pub struct NameEnumerator<'a> {
+    space: NameSpace<'a>,
+    capitalization: Capitalization,
+    counts: Vec<(u8, u128)>,
+    total: u128,
+    position: u128,
+    permutation: Option<Permutation>,
+}
+
+impl<'a> NameEnumerator<'a> {
+    pub(crate) fn new(language: &'a RNG, syllable_counts: RangeInclusive<u8>) -> Self {
+        let space = NameSpace::new(language, *syllable_counts.end());
+        let counts: Vec<(u8, u128)> = syllable_counts
+            .map(|count| (count, space.count(count)))
+            .filter(|(_, names)| *names > 0)
+            .collect();
+        let total = counts
+            .iter()
+            .fold(0u128, |sum, (_, names)| sum.saturating_add(*names));
+        NameEnumerator {
+            space,
+            capitalization: language.capitalization,
+            counts,
+            total,
+            position: 0,
+            permutation: None,
+        }
+    }
+
+    /// Hands out the names in a random order instead.
+    #[must_use]
+    pub fn shuffled<R: Rng + ?Sized>(mut self, rng: &mut R) -> Self {
+        self.permutation = Some(Permutation::new(self.total, rng));
+        self
+    }
+
+    /// How many names there are in all, including those already handed out.
+    #[must_use]
+    pub fn total(&self) -> u128 {
+        self.total
+    }
+
+    fn name(&self, mut index: u128) -> Option<String> {
+        for (count, names) in &self.counts {
+            if index < *names {
+                let syllables = self.space.unrank(*count, index)?;
+                let syllables: Syllables = syllables.into_iter().cloned().collect();
+                return Some(self.capitalization.apply(&syllables.collapse()));
+            }
+            index -= names;
+        }
+        None
+    }
+}
+
+impl Iterator for NameEnumerator<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position >= self.total {
+            return None;
+        }
+        let index = match &self.permutation {
+            Some(permutation) => permutation.apply(self.position),
+            None => self.position,
+        };
+        self.position += 1;
+        self.name(index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = usize::try_from(self.total - self.position).ok();
+        (remaining.unwrap_or(usize::MAX), remaining)
+    }
+}
+
+/// A random permutation of `0..size` that can be applied to one number at a time: a Feistel
+/// network over the smallest even number of bits that holds `size`, cycle walking until the
+/// result is back in range.
+struct Permutation {
+    size: u128,
+    half_bits: u32,
+    keys: [u64; 4],
+}
+
+impl Permutation {
+    fn new<R: Rng + ?Sized>(size: u128, rng: &mut R) -> Permutation {
+        let bits = (u128::BITS - size.saturating_sub(1).leading_zeros()).max(2);
+        Permutation {
+            size,
+            half_bits: bits.div_ceil(2),
+            keys: [
+                rng.next_u64(),
+                rng.next_u64(),
+                rng.next_u64(),
+                rng.next_u64(),
+            ],
+        }
+    }
+
+    fn apply(&self, index: u128) -> u128 {
+        let mut index = self.feistel(index);
+        while index >= self.size {
+            index = self.feistel(index);
+        }
+        index
+    }
+
+    fn feistel(&self, index: u128) -> u128 {
+        let mask = u64::MAX >> (64 - self.half_bits);
+        let mut left = u64::try_from(index >> self.half_bits).unwrap_or_default() & mask;
+        let mut right = u64::try_from(index & u128::from(mask)).unwrap_or_default();
+        for key in self.keys {
+            (left, right) = (right, left ^ (mix(right ^ key) & mask));
+        }
+        (u128::from(left) << self.half_bits) | u128::from(right)
+    }
+}
+
+/// The `SplitMix64` finalizer, which scrambles the bits of a number.
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod enumerate_tests {
+    use super::*;
+    use crate::{Language, LoadPolicy};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use rstest::rstest;
+    use std::collections::HashSet;
+
+    fn micro() -> RNG {
+        RNG::new_from_str("Micro".to_string(), "-ka\n-ri\nmo\nla\n+zu\n+do").unwrap()
+    }
+
+    #[test]
+    fn count() {
+        let rng = micro();
+        let space = NameSpace::new(&rng, 4);
+
+        assert_eq!(space.count(1), 0);
+        assert_eq!(space.count(2), 4);
+        assert_eq!(space.count(3), 8);
+        assert_eq!(space.count(4), 16);
+        assert_eq!(space.count(5), 0);
+    }
+
+    #[test]
+    fn count__duplicates() {
+        let rng = RNG::load("Micro".to_string(), "-ka\n-ka\n+zu", LoadPolicy::Lenient)
+            .unwrap()
+            .0;
+
+        assert_eq!(NameSpace::new(&rng, 2).count(2), 1);
+    }
+
+    #[rstest]
+    #[case(Language::Elven)]
+    #[case(Language::Goblin)]
+    #[case(Language::Roman)]
+    fn count__matches_brute_force(#[case] language: Language) {
+        let rng = RNG::try_from(&language).unwrap();
+        let space = NameSpace::new(&rng, 3);
+        let mut expected = 0u128;
+        for p in &space.prefixes {
+            for c in space.centers.iter().filter(|c| p.connects(c)) {
+                expected += space.suffixes.iter().filter(|s| c.connects(s)).count() as u128;
+            }
+        }
+
+        assert_eq!(space.count(3), expected);
+    }
+
+    #[test]
+    fn unrank() {
+        let rng = micro();
+        let space = NameSpace::new(&rng, 3);
+        let values = |index| -> Vec<String> {
+            space
+                .unrank(3, index)
+                .unwrap()
+                .iter()
+                .map(|s| s.value.clone())
+                .collect()
+        };
+
+        assert_eq!(values(0), vec!["ka", "mo", "zu"]);
+        assert_eq!(values(1), vec!["ka", "mo", "do"]);
+        assert_eq!(values(2), vec!["ka", "la", "zu"]);
+        assert_eq!(values(7), vec!["ri", "la", "do"]);
+        assert!(space.unrank(3, 8).is_none());
+    }
+
+    #[test]
+    fn rank() {
+        let rng = micro();
+        let space = NameSpace::new(&rng, 4);
+
+        for count in 2..=4 {
+            for index in 0..space.count(count) {
+                let name = space.unrank(count, index).unwrap();
+
+                assert_eq!(space.rank(&name), Some(index));
+            }
+        }
+    }
+
+    #[test]
+    fn rank__not_joined() {
+        let rng = RNG::new_from_str("Micro".to_string(), "-ka +v\n-ri\nmo\n+zu").unwrap();
+        let space = NameSpace::new(&rng, 2);
+        let suffix = space.suffixes[0];
+
+        assert_eq!(space.rank(&[space.prefixes[0], suffix]), None);
+        assert_eq!(space.rank(&[space.prefixes[1], suffix]), Some(0));
+    }
+
+    #[test]
+    fn grow() {
+        let rng = micro();
+        let mut space = NameSpace::new(&rng, 2);
+
+        space.grow();
+
+        assert_eq!(space.max_syllables(), 3);
+        assert_eq!(space.count(3), 8);
+    }
+
+    #[test]
+    fn enumerate_names() {
+        let rng = micro();
+
+        let names: Vec<String> = NameEnumerator::new(&rng, 2..=3).collect();
+
+        assert_eq!(names.len(), 12);
+        assert_eq!(names[..4], ["Kazu", "Kado", "Rizu", "Rido"]);
+        assert_eq!(names[4], "Kamozu");
+    }
+
+    #[test]
+    fn enumerate_names__obeys_joiners() {
+        let rng = RNG::try_from(&Language::Goblin).unwrap();
+        let space = NameSpace::new(&rng, 3);
+        let total = space.count(3);
+
+        for index in (0..total).step_by(97) {
+            let syllables = space.unrank(3, index).unwrap();
+            for pair in syllables.windows(2) {
+                assert!(pair[0].connects(pair[1]), "{syllables:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn shuffled__every_name_once() {
+        let rng = micro();
+        let mut random = StdRng::seed_from_u64(3);
+        let ordered: HashSet<String> = NameEnumerator::new(&rng, 2..=4).collect();
+
+        let shuffled: Vec<String> = NameEnumerator::new(&rng, 2..=4)
+            .shuffled(&mut random)
+            .collect();
+
+        assert_eq!(shuffled.len(), 28);
+        assert_eq!(shuffled.iter().cloned().collect::<HashSet<_>>(), ordered);
+        assert_ne!(
+            shuffled,
+            NameEnumerator::new(&rng, 2..=4).collect::<Vec<_>>()
+        );
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(2)]
+    #[case(3)]
+    #[case(1000)]
+    #[case(1023)]
+    #[case(1025)]
+    fn permutation__is_a_bijection(#[case] size: u128) {
+        let permutation = Permutation::new(size, &mut StdRng::seed_from_u64(5));
+
+        let values: HashSet<u128> = (0..size).map(|i| permutation.apply(i)).collect();
+
+        assert_eq!(values.len() as u128, size);
+        assert!(values.iter().all(|v| *v < size));
+    }
+
+    #[test]
+    fn permutation__large() {
+        let permutation = Permutation::new(u128::MAX, &mut StdRng::seed_from_u64(5));
+
+        assert!(permutation.apply(12345) < u128::MAX);
+    }
+
+    #[test]
+    fn size_hint() {
+        let rng = micro();
+        let mut names = NameEnumerator::new(&rng, 2..=2);
+        names.next();
+
+        assert_eq!(names.size_hint(), (3, Some(3)));
+    }
+}
@@ -0,0 +1,236 @@
+use std::error::Error;
+use std::fmt;
+
+/// The broad category of an `RNGError`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RNGErrorKind {
+    CyclicInheritance,
+    GenerationError,
+    InvalidDirective,
+    InvalidLanguageFile,
+    MissingParent,
+    ParsingError,
+    ReadError,
+    UnknownLanguage,
+    WriteError,
+}
+
+impl fmt::Display for RNGErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RNGErrorKind::CyclicInheritance => write!(f, "language inherits from itself"),
+            RNGErrorKind::GenerationError => write!(f, "unable to generate a name"),
+            RNGErrorKind::InvalidDirective => write!(f, "invalid directive"),
+            RNGErrorKind::InvalidLanguageFile => write!(f, "invalid language file"),
+            RNGErrorKind::MissingParent => write!(f, "unable to find parent language"),
+            RNGErrorKind::ParsingError => write!(f, "unable to parse syllable"),
+            RNGErrorKind::ReadError => write!(f, "unable to read language file"),
+            RNGErrorKind::UnknownLanguage => write!(f, "unknown language"),
+            RNGErrorKind::WriteError => write!(f, "unable to write language file"),
+        }
+    }
+}
+
+/// `RNGError` is the error returned by every fallible operation in the library. Beyond its
+/// `RNGErrorKind`, it carries whatever context was available where it happened: the offending
+/// file, the line number and raw text of the offending line, and the underlying error.
+///
+/// It implements `std::error::Error`, so it can be returned through `?` into `anyhow::Result` or
+/// `Box<dyn Error>`, and the underlying IO or UTF-8 error is available through `Error::source`.
+///
+/// ```
+/// use rnglib::{RNG, RNGErrorKind};
+///
+/// let error = RNG::new_from_file("missing.txt".to_string()).unwrap_err();
+///
+/// assert_eq!(error.kind(), RNGErrorKind::ReadError);
+/// assert_eq!(error.file(), Some("missing.txt"));
+/// assert_eq!(error.to_string(), "unable to read language file in missing.txt");
+/// ```
+#[derive(Debug)]
+pub struct RNGError {
+    kind: RNGErrorKind,
+    file: Option<String>,
+    line: Option<usize>,
+    text: Option<String>,
+    source: Option<Box<dyn Error + Send + Sync>>,
+}
+
+impl RNGError {
+    #[must_use]
+    pub fn new(kind: RNGErrorKind) -> RNGError {
+        RNGError {
+            kind,
+            file: None,
+            line: None,
+            text: None,
+            source: None,
+        }
+    }
+
+    /// Records the file the error happened in, unless a more specific one is already set.
+    #[must_use]
+    pub fn with_file(mut self, file: &str) -> RNGError {
+        self.file.get_or_insert_with(|| file.to_string());
+        self
+    }
+
+    /// Records the 1-based line number the error happened on.
+    #[must_use]
+    pub fn with_line(mut self, line: usize) -> RNGError {
+        self.line = Some(line);
+        self
+    }
+
+    /// Records the raw text that could not be processed.
+    #[must_use]
+    pub fn with_text(mut self, text: &str) -> RNGError {
+        self.text = Some(text.to_string());
+        self
+    }
+
+    #[must_use]
+    pub fn with_source(mut self, source: impl Error + Send + Sync + 'static) -> RNGError {
+        self.source = Some(Box::new(source));
+        self
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> RNGErrorKind {
+        self.kind
+    }
+
+    #[must_use]
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
+    #[must_use]
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    #[must_use]
+    pub fn text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+}
+
+impl fmt::Display for RNGError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, " in {file}:{line}")?,
+            (Some(file), None) => write!(f, " in {file}")?,
+            (None, Some(line)) => write!(f, " on line {line}")?,
+            (None, None) => {}
+        }
+        if let Some(text) = &self.text {
+            write!(f, ": {text:?}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for RNGError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_deref().map(|e| e as &(dyn Error + 'static))
+    }
+}
+
+impl From<RNGErrorKind> for RNGError {
+    fn from(kind: RNGErrorKind) -> Self {
+        RNGError::new(kind)
+    }
+}
+
+impl From<std::io::Error> for RNGError {
+    fn from(error: std::io::Error) -> Self {
+        let kind = match error.kind() {
+            std::io::ErrorKind::InvalidData => RNGErrorKind::InvalidLanguageFile,
+            _ => RNGErrorKind::ReadError,
+        };
+        RNGError::new(kind).with_source(error)
+    }
+}
+
+impl From<std::str::Utf8Error> for RNGError {
+    fn from(error: std::str::Utf8Error) -> Self {
+        RNGError::new(RNGErrorKind::InvalidLanguageFile).with_source(error)
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod error_tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let error = RNGError::new(RNGErrorKind::ParsingError);
+
+        assert_eq!(error.to_string(), "unable to parse syllable");
+    }
+
+    #[test]
+    fn display__full_context() {
+        let error = RNGError::new(RNGErrorKind::ParsingError)
+            .with_file("Elven.txt")
+            .with_line(12)
+            .with_text("-a!b");
+
+        assert_eq!(
+            error.to_string(),
+            "unable to parse syllable in Elven.txt:12: \"-a!b\""
+        );
+    }
+
+    #[test]
+    fn display__line_without_file() {
+        let error = RNGError::new(RNGErrorKind::ParsingError).with_line(3);
+
+        assert_eq!(error.to_string(), "unable to parse syllable on line 3");
+    }
+
+    #[test]
+    fn with_file__keeps_innermost() {
+        let error = RNGError::new(RNGErrorKind::ReadError)
+            .with_file("Child.txt")
+            .with_file("Parent.txt");
+
+        assert_eq!(error.file(), Some("Child.txt"));
+    }
+
+    #[test]
+    fn from_io_error() {
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "gone");
+
+        let error = RNGError::from(io);
+
+        assert_eq!(error.kind(), RNGErrorKind::ReadError);
+        assert_eq!(error.source().unwrap().to_string(), "gone");
+    }
+
+    #[test]
+    fn from_io_error__invalid_data() {
+        let io = std::io::Error::new(std::io::ErrorKind::InvalidData, "not utf-8");
+
+        assert_eq!(RNGError::from(io).kind(), RNGErrorKind::InvalidLanguageFile);
+    }
+
+    #[test]
+    fn anyhow() {
+        fn fails() -> anyhow::Result<()> {
+            Err(RNGError::new(RNGErrorKind::ReadError).with_file("x.txt"))?;
+            Ok(())
+        }
+
+        let error = fails().unwrap_err();
+
+        assert_eq!(
+            error.downcast_ref::<RNGError>().unwrap().kind(),
+            RNGErrorKind::ReadError
+        );
+        assert_eq!(error.to_string(), "unable to read language file in x.txt");
+    }
+}
@@ -0,0 +1,223 @@
+use crate::RNG;
+use crate::rng_joiner::Joiner;
+use crate::rng_segment::Pools;
+use crate::rng_weighted_rnd::NORMAL_WEIGHT;
+
+/// How well a name fits one language, returned by `RNG::identify`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Identification {
+    pub language: String,
+    /// How likely the language is to generate the name, see `RNG::likelihood`.
+    pub likelihood: f64,
+    /// The chance that the name came from this language rather than one of the others it was
+    /// scored against, taking each of them to be equally likely to begin with.
+    pub probability: f64,
+}
+
+/// How likely the language is to generate the name: the chance of each way of splitting it into
+/// syllables, weighted by how often names get that many syllables.
+///
+/// Rather than listing every split, which can take exponentially long, the chances are added up
+/// a byte at a time, for each joiner the last syllable ended with and each syllable count. Counts
+/// past the longest in `NORMAL_WEIGHT` all have the same chance, so they are kept together.
+pub(crate) fn likelihood(language: &RNG, name: &str) -> f64 {
+    let name = name.to_lowercase();
+    let pools = Pools::new(language);
+    let longest = NORMAL_WEIGHT
+        .counts
+        .iter()
+        .map(|count| usize::from(*count))
+        .max()
+        .unwrap_or_default();
+    // reached[offset][joiner][count]: the chance of syllables taking up the name up to the offset,
+    // with the last one ending with the joiner, and count of them, up to one more than the longest.
+    let mut reached = vec![vec![vec![0.0; longest + 2]; 16]; name.len() + 1];
+    for choice in &pools.prefixes {
+        if name.starts_with(choice.value.as_str()) {
+            let bits = usize::from(choice.syllable.jnext.bits());
+            reached[choice.value.len()][bits][1] += choice.chance;
+        }
+    }
+
+    let mut likelihood = 0.0;
+    for offset in 1..name.len() {
+        if !name.is_char_boundary(offset) {
+            continue;
+        }
+        let rest = &name[offset..];
+        for bits in 0u8..16 {
+            let last = Joiner::from_bits_retain(bits);
+            for count in 1..=longest + 1 {
+                let probability = reached[offset][usize::from(bits)][count];
+                if probability == 0.0 {
+                    continue;
+                }
+                let next = (count + 1).min(longest + 1);
+                for choice in pools.suffixes_after(last) {
+                    if rest == choice.value {
+                        likelihood += count_chance(next) * probability * choice.chance;
+                    }
+                }
+                for choice in pools.centers_after(last) {
+                    if rest.len() > choice.value.len() && rest.starts_with(choice.value.as_str()) {
+                        let after = &mut reached[offset + choice.value.len()];
+                        after[usize::from(choice.syllable.jnext.bits())][next] +=
+                            probability * choice.chance;
+                    }
+                }
+            }
+        }
+    }
+    likelihood
+}
+
+/// Scores the name against every language, leaving out those that couldn't have made it, most
+/// likely first.
+pub(crate) fn identify<'a>(
+    name: &str,
+    languages: impl IntoIterator<Item = &'a RNG>,
+) -> Vec<Identification> {
+    let mut found: Vec<Identification> = languages
+        .into_iter()
+        .map(|language| Identification {
+            language: language.name.clone(),
+            likelihood: likelihood(language, name),
+            probability: 0.0,
+        })
+        .filter(|identification| identification.likelihood > 0.0)
+        .collect();
+
+    let total: f64 = found.iter().map(|i| i.likelihood).sum();
+    for identification in &mut found {
+        identification.probability = identification.likelihood / total;
+    }
+    found.sort_by(|a, b| b.likelihood.total_cmp(&a.likelihood));
+    found
+}
+
+/// The chance of a generated name having this many syllables. Names can be made longer than
+/// `generate_name` makes them, so those counts are given the weight of the rarest count rather
+/// than none.
+fn count_chance(count: usize) -> f64 {
+    let weights = &NORMAL_WEIGHT.weights;
+    let weight = NORMAL_WEIGHT
+        .counts
+        .iter()
+        .position(|c| usize::from(*c) == count)
+        .map_or_else(
+            || weights.iter().min().copied().unwrap_or(1),
+            |i| weights[i],
+        );
+    let total: u32 = weights.iter().map(|w| u32::from(*w)).sum();
+    f64::from(weight) / f64::from(total)
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod identify_tests {
+    use super::*;
+    use crate::{Language, LoadPolicy};
+    use rstest::rstest;
+
+    /// Loads a test language, which can leave out centers to keep names to two syllables.
+    fn micro(name: &str, raw: &str) -> RNG {
+        RNG::load(name.to_string(), raw, LoadPolicy::Lenient)
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn likelihood() {
+        let rng = micro("Micro", "-ka *3\n-ri\nmo\n+zu\n+do");
+
+        assert!((super::likelihood(&rng, "kamozu") - 10.0 / 18.0 * 0.375).abs() < 1e-12);
+        assert!((super::likelihood(&rng, "Rizu") - 4.0 / 18.0 * 0.125).abs() < 1e-12);
+        assert!(super::likelihood(&rng, "Xyzzy").abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn likelihood__sums_segmentations() {
+        let rng = micro("Micro", "-ka\n-kaz\n+zu\n+u");
+
+        assert!((super::likelihood(&rng, "Kazu") - 4.0 / 18.0 * 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn likelihood__matches_segmentations() {
+        let rng = RNG::try_from(&Language::Goblin).unwrap();
+
+        for _ in 0..50 {
+            let name = rng.generate_name();
+            let expected: f64 = crate::rng_segment::segment(&rng, &name)
+                .iter()
+                .map(|(syllables, p)| super::count_chance(syllables.len()) * p)
+                .sum();
+
+            assert!(
+                (super::likelihood(&rng, &name) - expected).abs() < 1e-12,
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn likelihood__long_runs() {
+        let rng = micro("Runs", "-ka\na\naa\n+zu");
+
+        let likelihood = super::likelihood(&rng, &format!("ka{}zu", "a".repeat(500)));
+
+        assert!(likelihood > 0.0);
+    }
+
+    #[rstest]
+    #[case(2, 4.0 / 18.0)]
+    #[case(3, 10.0 / 18.0)]
+    #[case(5, 1.0 / 18.0)]
+    #[case(7, 1.0 / 18.0)]
+    fn count_chance(#[case] count: usize, #[case] expected: f64) {
+        assert!((super::count_chance(count) - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn identify__ranks() {
+        let first = micro("First", "-ka\n-ri\n+zu\n+do");
+        let second = micro("Second", "-ka\n+zu");
+        let third = micro("Third", "-gr\n+uk");
+
+        let found = identify("Kazu", [&first, &second, &third]);
+
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].language, "Second");
+        assert_eq!(found[1].language, "First");
+        assert!((found[0].probability - 0.8).abs() < 1e-12);
+        assert!((found[1].probability - 0.2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn identify__no_language() {
+        let rng = micro("Micro", "-ka\n+zu");
+
+        assert!(identify("Grok", [&rng]).is_empty());
+    }
+
+    #[rstest]
+    #[case(Language::Elven)]
+    #[case(Language::Goblin)]
+    #[case(Language::Roman)]
+    fn identify__generated_names(#[case] language: Language) {
+        let languages: Vec<RNG> = [Language::Elven, Language::Goblin, Language::Roman]
+            .iter()
+            .map(|l| RNG::try_from(l).unwrap())
+            .collect();
+        let rng = RNG::try_from(&language).unwrap();
+
+        for _ in 0..20 {
+            let name = rng.generate_name();
+            let found = identify(&name, &languages);
+
+            assert!(found.iter().any(|i| i.language == rng.name), "{name}");
+            let total: f64 = found.iter().map(|i| i.probability).sum();
+            assert!((total - 1.0).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,790 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::rng_syllable::{Classification, Syllable};
+use crate::{
+    COMPILED_EXTENSION, Capitalization, Language, LanguageRegistry, LanguageSource, RNG, RNGError,
+    RNGErrorKind, Script,
+};
+
+/// How to treat lines in a language file that are not valid syllables.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LoadPolicy {
+    /// Fail on the first line that cannot be parsed.
+    #[default]
+    Strict,
+    /// Skip lines that cannot be parsed, recording each one in the `LoadReport`.
+    Lenient,
+}
+
+/// A single line, or the language as a whole, that deserves the caller's attention.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoadIssue {
+    /// The parent language file the issue was found in, or `None` if it is in the language
+    /// being loaded.
+    pub file: Option<String>,
+    /// 1-based line number, or `None` if the issue concerns the language as a whole.
+    pub line: Option<usize>,
+    pub text: String,
+    pub message: String,
+}
+
+impl fmt::Display for LoadIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{file}: ")?;
+        }
+        match self.line {
+            Some(line) => write!(f, "line {line}: {:?}: {}", self.text, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Everything noteworthy found while loading a language, so applications can decide for
+/// themselves what is acceptable.
+///
+/// * `skipped` - lines that could not be parsed (only ever populated in lenient mode).
+/// * `duplicates` - syllables declared more than once. They are kept, since the repetition
+///   makes the syllable more likely to be chosen, but are usually a mistake.
+/// * `warnings` - problems with the language as a whole, such as an empty set of suffixes.
+///
+/// Issues found in the file of a parent language, see `@extends`, are included, with the
+/// parent's file as their `file`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LoadReport {
+    pub skipped: Vec<LoadIssue>,
+    pub duplicates: Vec<LoadIssue>,
+    pub warnings: Vec<LoadIssue>,
+}
+
+impl LoadReport {
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.skipped.is_empty() && self.duplicates.is_empty() && self.warnings.is_empty()
+    }
+}
+
+/// Classifies a language file one line at a time, applying a `LoadPolicy`. Blank lines are
+/// ignored.
+///
+/// Lines starting with `@` are directives, which let a language build on another one:
+///
+/// * `@extends NAME` - starts from the language with that name, looked up in the registry the
+///   file was found through, or `LanguageRegistry::from_env` for a file loaded directly, or from
+///   the language file at that path, relative to the file being loaded. Syllables declared in the file are added to the parent's, and a syllable with
+///   the same value as one of the parent's, ignoring case, replaces it, which is how it can be
+///   re-weighted.
+/// * `@remove SYLLABLE` - leaves one of the parent's syllables out, such as `@remove -ael`.
+///
+/// A language written in phonemes gives their spellings with another directive, see
+/// `Orthography`:
+///
+/// * `@spell SCRIPT PHONEME SPELLING` - spells the phoneme in the script, such as
+///   `@spell cyrillic ʃ ш`. Spellings override the parent's for the same phoneme.
+///
+/// * `@capitalize POLICY` - capitalizes names by `first` letter, the default, after each
+///   `apostrophe` as well, or `preserve`s the case of the syllables, or capitalizes `none` of
+///   them. See `Capitalization`. Without one, a language capitalizes names like its parent.
+pub(crate) struct Loader {
+    rng: RNG,
+    report: LoadReport,
+    policy: LoadPolicy,
+    line: usize,
+    origin: Option<PathBuf>,
+    /// Files being loaded, outermost first, to catch languages that inherit from themselves.
+    chain: Vec<String>,
+    /// Where `@extends NAME` is looked up, or `LanguageRegistry::from_env` if unset.
+    registry: Option<LanguageRegistry>,
+    extends: Option<(String, usize)>,
+    capitalization: Option<Capitalization>,
+    removals: Vec<(Syllable, usize, String)>,
+}
+
+impl Loader {
+    pub(crate) fn new(name: String, policy: LoadPolicy) -> Loader {
+        Loader::from_rng(RNG::empty(name), policy)
+    }
+
+    /// Starts from an already classified language, such as a compiled one, so that `finish`
+    /// can still check it as a whole.
+    pub(crate) fn from_rng(rng: RNG, policy: LoadPolicy) -> Loader {
+        Loader {
+            rng,
+            report: LoadReport::default(),
+            policy,
+            line: 0,
+            origin: None,
+            chain: Vec::new(),
+            registry: None,
+            extends: None,
+            capitalization: None,
+            removals: Vec::new(),
+        }
+    }
+
+    /// Records the file being loaded, which relative `@extends` paths are resolved against, and
+    /// the files that are already being loaded on its behalf.
+    pub(crate) fn with_origin(mut self, path: &Path, mut chain: Vec<String>) -> Loader {
+        chain.push(file_id(path));
+        self.origin = Some(path.to_path_buf());
+        self.chain = chain;
+        self
+    }
+
+    /// Looks up the parents named by `@extends` in the registry, rather than
+    /// `LanguageRegistry::from_env`.
+    pub(crate) fn with_registry(mut self, registry: LanguageRegistry) -> Loader {
+        self.registry = Some(registry);
+        self
+    }
+
+    pub(crate) fn lines(&mut self, lines: &str) -> Result<(), RNGError> {
+        for line in lines.lines() {
+            self.line(line)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn line(&mut self, text: &str) -> Result<(), RNGError> {
+        self.line += 1;
+        if text.trim().is_empty() {
+            return Ok(());
+        }
+
+        let result = if text.trim_start().starts_with('@') {
+            self.directive(text)
+        } else {
+            Syllable::from_str(text).map(|syllable| self.add(syllable, text))
+        };
+        match result {
+            Ok(()) => Ok(()),
+            Err(e) => match self.policy {
+                LoadPolicy::Strict => Err(e.with_line(self.line)),
+                LoadPolicy::Lenient => {
+                    self.report.skipped.push(LoadIssue {
+                        file: None,
+                        line: Some(self.line),
+                        text: text.to_string(),
+                        message: e.kind().to_string(),
+                    });
+                    Ok(())
+                }
+            },
+        }
+    }
+
+    fn directive(&mut self, text: &str) -> Result<(), RNGError> {
+        let error = || RNGError::new(RNGErrorKind::InvalidDirective).with_text(text);
+        let (directive, argument) = text
+            .trim()
+            .split_once(char::is_whitespace)
+            .map(|(directive, argument)| (directive, argument.trim()))
+            .ok_or_else(error)?;
+
+        match directive {
+            "@extends" if self.extends.is_none() => {
+                self.extends = Some((argument.to_string(), self.line));
+            }
+            "@remove" => {
+                let syllable = Syllable::from_str(argument).map_err(|_| error())?;
+                self.removals.push((syllable, self.line, text.to_string()));
+            }
+            "@capitalize" if self.capitalization.is_none() => {
+                self.capitalization =
+                    Some(Capitalization::from_str(argument).map_err(|_| error())?);
+            }
+            "@spell" => {
+                let [script, phoneme, spelling] = argument
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .try_into()
+                    .map_err(|_| error())?;
+                let script = Script::from_str(script).map_err(|_| error())?;
+                self.rng.orthography.add(script, phoneme, spelling);
+            }
+            _ => return Err(error()),
+        }
+        Ok(())
+    }
+
+    fn add(&mut self, syllable: Syllable, text: &str) {
+        let syllables = match syllable.classification {
+            Classification::Prefix => &mut self.rng.prefixes,
+            Classification::Center => &mut self.rng.centers,
+            Classification::Suffix => &mut self.rng.suffixes,
+        };
+        if syllables.contains(&syllable) {
+            self.report.duplicates.push(LoadIssue {
+                file: None,
+                line: Some(self.line),
+                text: text.to_string(),
+                message: format!("duplicate syllable {syllable}"),
+            });
+        }
+        syllables.add(syllable);
+    }
+
+    /// Resolves `@extends`, `@remove` and `@capitalize`, then checks the language as a whole.
+    ///
+    /// # Errors
+    ///
+    /// Errors out if the parent language can't be found or loaded, or inherits from this one,
+    /// or with `RNGErrorKind::InvalidLanguageFile` if, under `LoadPolicy::Strict`, the language
+    /// has no prefix, center or suffix syllables.
+    pub(crate) fn finish(mut self) -> Result<(RNG, LoadReport), RNGError> {
+        if let Some((parent, line)) = self.extends.take() {
+            // Errors from within the parent's own file already say where they happened.
+            let parent = self.load_parent(parent.as_str()).map_err(|e| {
+                if e.file().is_some() {
+                    e
+                } else {
+                    e.with_line(line)
+                }
+            })?;
+            self.inherit(parent);
+        }
+        if let Some(capitalization) = self.capitalization {
+            self.rng.capitalization = capitalization;
+        }
+        for (_, line, text) in std::mem::take(&mut self.removals) {
+            self.report.warnings.push(LoadIssue {
+                file: None,
+                line: Some(line),
+                text,
+                message: "no inherited syllable to remove".to_string(),
+            });
+        }
+
+        // A parent is checked as part of the language that extends it.
+        if self.chain.len() > 1 {
+            return Ok((self.rng, self.report));
+        }
+        for (syllables, label) in [
+            (&self.rng.prefixes, "prefix"),
+            (&self.rng.centers, "center"),
+            (&self.rng.suffixes, "suffix"),
+        ] {
+            if !syllables.is_empty() {
+                continue;
+            }
+            if self.policy == LoadPolicy::Strict {
+                return Err(
+                    RNGError::new(RNGErrorKind::InvalidLanguageFile).with_source(EmptyPool(label))
+                );
+            }
+            self.report.warnings.push(LoadIssue {
+                file: None,
+                line: None,
+                text: String::new(),
+                message: EmptyPool(label).to_string(),
+            });
+        }
+        Ok((self.rng, self.report))
+    }
+
+    /// Loads the parent language, adding the issues found in its file to this language's report.
+    fn load_parent(&mut self, parent: &str) -> Result<RNG, RNGError> {
+        let missing = || RNGError::new(RNGErrorKind::MissingParent).with_text(parent);
+
+        let path = if is_path(parent) {
+            let base = self.origin.as_deref().and_then(Path::parent);
+            let path = base.map_or_else(|| PathBuf::from(parent), |base| base.join(parent));
+            if !path.is_file() {
+                return Err(missing());
+            }
+            path
+        } else {
+            match self
+                .registry
+                .get_or_insert_with(LanguageRegistry::from_env)
+                .find(parent)
+                .ok_or_else(missing)?
+                .source
+            {
+                LanguageSource::BuiltIn(language) => return RNG::new(&language),
+                LanguageSource::File(path) => {
+                    // A user file that extends the built-in language it shadows.
+                    match Language::from_str(parent) {
+                        Ok(language) if self.chain.contains(&file_id(&path)) => {
+                            return RNG::new(&language);
+                        }
+                        _ => path,
+                    }
+                }
+            }
+        };
+
+        if self.chain.contains(&file_id(&path)) {
+            let mut cycle = self.chain.clone();
+            cycle.push(file_id(&path));
+            return Err(
+                RNGError::new(RNGErrorKind::CyclicInheritance).with_text(&cycle.join(" -> "))
+            );
+        }
+        let file = path.to_string_lossy().to_string();
+        let registry = self
+            .registry
+            .get_or_insert_with(LanguageRegistry::from_env)
+            .clone();
+        let (rng, report) = RNG::load_path(
+            &path,
+            file.clone(),
+            self.policy,
+            self.chain.clone(),
+            &registry,
+        )?;
+        self.merge(report, &file);
+        Ok(rng)
+    }
+
+    /// Adds the issues from a parent's report, tagged with the parent's file unless they came
+    /// from further up.
+    fn merge(&mut self, report: LoadReport, file: &str) {
+        let tag = |mut issue: LoadIssue| {
+            issue.file.get_or_insert_with(|| file.to_string());
+            issue
+        };
+        self.report
+            .skipped
+            .extend(report.skipped.into_iter().map(tag));
+        self.report
+            .duplicates
+            .extend(report.duplicates.into_iter().map(tag));
+        self.report
+            .warnings
+            .extend(report.warnings.into_iter().map(tag));
+    }
+
+    /// Makes the parent the starting point, applying this language's removals and syllables on
+    /// top of it.
+    fn inherit(&mut self, mut parent: RNG) {
+        let removals = std::mem::take(&mut self.removals);
+        for (syllable, line, text) in removals {
+            let syllables = match syllable.classification {
+                Classification::Prefix => &mut parent.prefixes,
+                Classification::Center => &mut parent.centers,
+                Classification::Suffix => &mut parent.suffixes,
+            };
+            if syllables.remove(syllable.value.as_str()) == 0 {
+                self.removals.push((syllable, line, text));
+            }
+        }
+
+        for (inherited, own) in [
+            (&mut parent.prefixes, &self.rng.prefixes),
+            (&mut parent.centers, &self.rng.centers),
+            (&mut parent.suffixes, &self.rng.suffixes),
+        ] {
+            for syllable in own.all() {
+                inherited.replace(syllable.clone());
+            }
+        }
+        parent.orthography.extend(&self.rng.orthography);
+        parent.name = std::mem::take(&mut self.rng.name);
+        self.rng = parent;
+    }
+}
+
+/// `@extends` arguments that look like a file, rather than a language name.
+fn is_path(parent: &str) -> bool {
+    parent.contains(['/', '\\'])
+        || Path::new(parent)
+            .extension()
+            .is_some_and(|ext| ext == "txt" || ext == COMPILED_EXTENSION)
+}
+
+/// A pool of the language, named by its label, that has no syllables. Surfaces as the source of
+/// an `RNGErrorKind::InvalidLanguageFile` error under `LoadPolicy::Strict`.
+#[derive(Debug)]
+struct EmptyPool(&'static str);
+
+impl fmt::Display for EmptyPool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "language has no {} syllables", self.0)
+    }
+}
+
+impl std::error::Error for EmptyPool {}
+
+/// Identifies a file regardless of how the path to it was written.
+fn file_id(path: &Path) -> String {
+    std::fs::canonicalize(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod load_tests {
+    use super::*;
+    use rstest::rstest;
+    use std::error::Error;
+
+    fn load(text: &str, policy: LoadPolicy) -> Result<(RNG, LoadReport), RNGError> {
+        let mut loader = Loader::new("Test".to_string(), policy);
+        loader.lines(text)?;
+        loader.finish()
+    }
+
+    #[test]
+    fn strict() {
+        let (rng, report) = load("-a\nb\n+c", LoadPolicy::Strict).unwrap();
+
+        assert_eq!(rng.prefixes.len(), 1);
+        assert_eq!(rng.centers.len(), 1);
+        assert_eq!(rng.suffixes.len(), 1);
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn strict__fails_on_first_bad_line() {
+        let error = load("-a\n!!\nb\n#$\n+c", LoadPolicy::Strict).unwrap_err();
+
+        assert_eq!(error.kind(), RNGErrorKind::ParsingError);
+        assert_eq!(error.line(), Some(2));
+        assert_eq!(error.text(), Some("!!"));
+    }
+
+    #[test]
+    fn lenient__skips_bad_lines() {
+        let (rng, report) = load("-a\n!!\nb\n#$\n+c", LoadPolicy::Lenient).unwrap();
+
+        assert_eq!(rng.prefixes.len(), 1);
+        assert_eq!(rng.centers.len(), 1);
+        assert_eq!(rng.suffixes.len(), 1);
+        assert_eq!(report.skipped.len(), 2);
+        assert_eq!(report.skipped[0].line, Some(2));
+        assert_eq!(report.skipped[0].text, "!!");
+        assert_eq!(report.skipped[1].line, Some(4));
+        assert_eq!(
+            report.skipped[1].to_string(),
+            "line 4: \"#$\": unable to parse syllable"
+        );
+    }
+
+    #[test]
+    fn blank_lines_are_ignored() {
+        let (rng, report) = load("-a\n\n  \nb\n+c\n", LoadPolicy::Strict).unwrap();
+
+        assert_eq!(rng.syllables().len(), 3);
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn duplicates() {
+        let (rng, report) = load("-a\n-b\n-a\nb\n+c\n+c -v", LoadPolicy::Strict).unwrap();
+
+        assert_eq!(rng.prefixes.len(), 3);
+        assert_eq!(rng.suffixes.len(), 2);
+        assert_eq!(report.duplicates.len(), 1);
+        assert_eq!(report.duplicates[0].line, Some(3));
+        assert_eq!(report.duplicates[0].message, "duplicate syllable -a");
+    }
+
+    #[test]
+    fn strict__weights_past_u32_max() {
+        let (rng, report) = load(
+            "-ka *4000000000\n-zo *300000000\nri\n+zu",
+            LoadPolicy::Strict,
+        )
+        .unwrap();
+
+        assert_eq!(rng.prefixes.total_weight(), 4_300_000_000);
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn warnings__empty_sections() {
+        let (_, report) = load("-a\n+c", LoadPolicy::Lenient).unwrap();
+
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(
+            report.warnings[0].to_string(),
+            "language has no center syllables"
+        );
+    }
+
+    #[rstest]
+    #[case("+c\nb", "prefix")]
+    #[case("-a\n+c", "center")]
+    #[case("-ka\nb", "suffix")]
+    fn strict__empty_sections(#[case] text: &str, #[case] label: &str) {
+        let error = load(text, LoadPolicy::Strict).unwrap_err();
+
+        assert_eq!(error.kind(), RNGErrorKind::InvalidLanguageFile);
+        assert_eq!(error.text(), None);
+        assert_eq!(
+            error.source().unwrap().to_string(),
+            format!("language has no {label} syllables")
+        );
+    }
+
+    /// Writes the files into a fresh scratch directory, returning its path.
+    fn scratch(label: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rng-load-{label}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, contents) in files {
+            std::fs::write(dir.join(name), contents).unwrap();
+        }
+        dir
+    }
+
+    fn load_path(path: &Path) -> Result<(RNG, LoadReport), RNGError> {
+        RNG::load_file(path.to_string_lossy().to_string(), LoadPolicy::Strict)
+    }
+
+    #[test]
+    fn extends__built_in() {
+        let (rng, report) = load(
+            "@extends Goblin\n@remove -ваа\n-zog\n+быр *5",
+            LoadPolicy::Strict,
+        )
+        .unwrap();
+        let goblin = RNG::try_from(&Language::Goblin).unwrap();
+
+        assert_eq!(rng.name, "Test");
+        assert_eq!(rng.centers, goblin.centers);
+        assert_eq!(rng.prefixes.len(), goblin.prefixes.len() + 1);
+        assert_eq!(rng.prefixes.last().unwrap().value, "zog");
+        assert_eq!(rng.suffixes.len(), goblin.suffixes.len() + 1);
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(
+            report.warnings[0].to_string(),
+            "line 2: \"@remove -ваа\": no inherited syllable to remove"
+        );
+    }
+
+    #[test]
+    fn extends__remove_and_reweight() {
+        // Without an origin, relative paths are relative to the working directory.
+        let (rng, report) = load(
+            "@extends src/languages/Test-micro.txt\n@remove b\nd\n+c *4",
+            LoadPolicy::Strict,
+        )
+        .unwrap();
+
+        assert_eq!(rng.to_language_file(), "-a\nd\n+c *4\n");
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn extends__file_relative_to_origin() {
+        let dir = scratch(
+            "relative",
+            &[
+                ("Base.txt", "-ka\n-zo\nri\n+zu"),
+                ("High.txt", "@extends Base.txt\n@remove -zo\n-ael *3"),
+            ],
+        );
+
+        let (rng, _) = load_path(&dir.join("High.txt")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(rng.to_language_file(), "-ka\n-ael *3\nri\n+zu\n");
+        assert!(rng.name.ends_with("High.txt"));
+    }
+
+    #[test]
+    fn extends__parent_report() {
+        let dir = scratch(
+            "report",
+            &[
+                ("Base.txt", "-ka\n-ka\n!!\nri"),
+                ("High.txt", "@extends Base.txt\n+zu"),
+            ],
+        );
+        let base = dir.join("Base.txt").to_string_lossy().to_string();
+
+        let (_, report) = RNG::load_file(
+            dir.join("High.txt").to_string_lossy().to_string(),
+            LoadPolicy::Lenient,
+        )
+        .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].file.as_deref(), Some(base.as_str()));
+        assert_eq!(report.skipped[0].line, Some(3));
+        assert_eq!(report.duplicates.len(), 1);
+        assert_eq!(
+            report.duplicates[0].to_string(),
+            format!("{base}: line 2: \"-ka\": duplicate syllable -ka")
+        );
+        // Base.txt has no suffixes of its own, but High.txt does.
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn extends__chain() {
+        let dir = scratch(
+            "chain",
+            &[
+                ("A.txt", "-a\nb\n+c"),
+                ("B.txt", "@extends A.txt\n-d"),
+                ("C.txt", "@extends B.txt\n@remove -a"),
+            ],
+        );
+
+        let (rng, _) = load_path(&dir.join("C.txt")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(rng.to_language_file(), "-d\nb\n+c\n");
+    }
+
+    #[test]
+    fn extends__cycle() {
+        let dir = scratch(
+            "cycle",
+            &[
+                ("A.txt", "@extends B.txt\n-a\nb\n+c"),
+                ("B.txt", "\n@extends ./A.txt\n-d"),
+            ],
+        );
+
+        let error = load_path(&dir.join("A.txt")).unwrap_err();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(error.kind(), RNGErrorKind::CyclicInheritance);
+        assert_eq!(error.line(), Some(2));
+        assert!(error.file().unwrap().ends_with("B.txt"));
+        let text = error.text().unwrap();
+        assert!(text.ends_with("A.txt"), "{text}");
+        assert_eq!(text.matches(" -> ").count(), 2);
+    }
+
+    #[test]
+    fn extends__self() {
+        let dir = scratch("self", &[("A.txt", "@extends A.txt\n-a\nb\n+c")]);
+
+        let error = load_path(&dir.join("A.txt")).unwrap_err();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(error.kind(), RNGErrorKind::CyclicInheritance);
+        assert_eq!(error.line(), Some(1));
+    }
+
+    #[test]
+    fn extends__missing_parent() {
+        let error = load("-a\n@extends Vulcan\nb\n+c", LoadPolicy::Lenient).unwrap_err();
+
+        assert_eq!(error.kind(), RNGErrorKind::MissingParent);
+        assert_eq!(error.line(), Some(2));
+        assert_eq!(
+            error.to_string(),
+            "unable to find parent language on line 2: \"Vulcan\""
+        );
+    }
+
+    #[test]
+    fn extends__missing_parent_file() {
+        let error = load("@extends nowhere/Vulcan.txt", LoadPolicy::Strict).unwrap_err();
+
+        assert_eq!(error.kind(), RNGErrorKind::MissingParent);
+        assert_eq!(error.text(), Some("nowhere/Vulcan.txt"));
+    }
+
+    #[test]
+    fn extends__compiled_parent() {
+        let compiled = RNG::new_from_str("Base".to_string(), "-ka\nri\n+zu").unwrap();
+        let dir = scratch("compiled", &[("Child.txt", "@extends Base.rngc\n-to")]);
+        std::fs::write(dir.join("Base.rngc"), compiled.compile().unwrap()).unwrap();
+
+        let (rng, _) = load_path(&dir.join("Child.txt")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(rng.to_language_file(), "-ka\n-to\nri\n+zu\n");
+    }
+
+    #[test]
+    fn spell() {
+        let (rng, report) = load(
+            "@spell latin ʃ sh\n@spell Cyrillic ʃ ш\n-ʃa\nri\n+zu",
+            LoadPolicy::Strict,
+        )
+        .unwrap();
+
+        assert_eq!(rng.orthography.spelling(Script::Latin, "ʃ"), Some("sh"));
+        assert_eq!(rng.orthography.spelling(Script::Cyrillic, "ʃ"), Some("ш"));
+        assert_eq!(rng.prefixes.all()[0].value, "ʃa");
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn capitalize() {
+        let (rng, report) =
+            load("@capitalize apostrophe\n-qo'\nno\n+s", LoadPolicy::Strict).unwrap();
+
+        assert_eq!(rng.capitalization, Capitalization::AfterApostrophe);
+        assert_eq!(rng.generate_name_by_count(3), "Qo'Nos");
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn extends__capitalization() {
+        let dir = scratch(
+            "capitalization",
+            &[
+                ("Base.txt", "@capitalize preserve\n-d'\nAr\n+c"),
+                ("Same.txt", "@extends Base.txt\n-l'"),
+                ("Lower.txt", "@extends Base.txt\n@capitalize none"),
+            ],
+        );
+
+        let (same, _) = load_path(&dir.join("Same.txt")).unwrap();
+        let (lower, _) = load_path(&dir.join("Lower.txt")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(same.capitalization, Capitalization::Preserve);
+        assert_eq!(lower.capitalization, Capitalization::None);
+    }
+
+    #[test]
+    fn extends__spellings() {
+        let dir = scratch(
+            "spellings",
+            &[
+                (
+                    "Base.txt",
+                    "@spell latin ʃ sh\n@spell latin ŋ ng\n-ʃa\nri\n+ŋu",
+                ),
+                ("Child.txt", "@extends Base.txt\n@spell latin ʃ sch"),
+            ],
+        );
+
+        let (rng, _) = load_path(&dir.join("Child.txt")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(rng.orthography.spelling(Script::Latin, "ʃ"), Some("sch"));
+        assert_eq!(rng.orthography.spelling(Script::Latin, "ŋ"), Some("ng"));
+    }
+
+    #[rstest]
+    #[case("@extends")]
+    #[case("@include Elven")]
+    #[case("@remove !!")]
+    #[case("@capitalize title")]
+    #[case("@capitalize none\n@capitalize first")]
+    #[case("@spell latin ʃ")]
+    #[case("@spell greek ʃ σ")]
+    #[case("@spell latin ʃ sh s")]
+    #[case("@extends Elven\n@extends Goblin")]
+    fn directive__invalid(#[case] text: &str) {
+        let error = load(text, LoadPolicy::Strict).unwrap_err();
+
+        assert_eq!(error.kind(), RNGErrorKind::InvalidDirective);
+    }
+
+    #[test]
+    fn directive__invalid__lenient() {
+        let (_, report) = load("@include Elven\n-a\nb\n+c", LoadPolicy::Lenient).unwrap();
+
+        assert_eq!(
+            report.skipped[0].to_string(),
+            "line 1: \"@include Elven\": invalid directive"
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use crate::Script;
+
+/// `Orthography` spells out a language written in phonemes, such as `ʃ` and `ŋ`, in one or more
+/// scripts. Each phoneme is given a spelling per script, so that one language file can stand in
+/// for both `Goblin.txt` and `Гоблин.txt`.
+///
+/// Spellings are declared in language files with the `@spell` directive, one per line:
+///
+/// ```text
+/// @spell latin ʃ sh
+/// @spell cyrillic ʃ ш
+/// -ʃak
+/// ```
+///
+/// A phoneme can be more than one letter, in which case the longest one that fits is spelled
+/// first. Anything without a spelling is written as it is. See `RNG::in_script`.
+///
+/// ```
+/// use rnglib::{Orthography, Script};
+///
+/// let orthography = Orthography::new()
+///     .with_spelling(Script::Latin, "ʃ", "sh")
+///     .with_spelling(Script::Cyrillic, "ʃ", "ш")
+///     .with_spelling(Script::Cyrillic, "a", "а");
+///
+/// assert_eq!(orthography.spell("ʃa", Script::Latin), "sha");
+/// assert_eq!(orthography.spell("ʃa", Script::Cyrillic), "ша");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Orthography {
+    spellings: Vec<(Script, String, String)>,
+}
+
+impl Orthography {
+    #[must_use]
+    pub fn new() -> Orthography {
+        Orthography::default()
+    }
+
+    /// Spells the phoneme in the script, replacing any spelling it already had there.
+    #[must_use]
+    pub fn with_spelling(mut self, script: Script, phoneme: &str, spelling: &str) -> Orthography {
+        self.add(script, phoneme, spelling);
+        self
+    }
+
+    pub(crate) fn add(&mut self, script: Script, phoneme: &str, spelling: &str) {
+        match self
+            .spellings
+            .iter_mut()
+            .find(|(s, p, _)| *s == script && p == phoneme)
+        {
+            Some((_, _, existing)) => *existing = spelling.to_string(),
+            None => self
+                .spellings
+                .push((script, phoneme.to_string(), spelling.to_string())),
+        }
+    }
+
+    /// Adds the other orthography's spellings, which win over any of these for the same phoneme.
+    pub(crate) fn extend(&mut self, other: &Orthography) {
+        for (script, phoneme, spelling) in other.spellings() {
+            self.add(script, phoneme, spelling);
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.spellings.is_empty()
+    }
+
+    /// The scripts that at least one phoneme is spelled in, in the order they were first given.
+    #[must_use]
+    pub fn scripts(&self) -> Vec<Script> {
+        let mut scripts = Vec::new();
+        for (script, _, _) in &self.spellings {
+            if !scripts.contains(script) {
+                scripts.push(*script);
+            }
+        }
+        scripts
+    }
+
+    /// How the phoneme is spelled in the script, if it has been given a spelling there.
+    #[must_use]
+    pub fn spelling(&self, script: Script, phoneme: &str) -> Option<&str> {
+        self.spellings
+            .iter()
+            .find(|(s, p, _)| *s == script && p == phoneme)
+            .map(|(_, _, spelling)| spelling.as_str())
+    }
+
+    /// Every spelling, as the script, phoneme and spelling, in the order they were given.
+    pub(crate) fn spellings(&self) -> impl Iterator<Item = (Script, &str, &str)> {
+        self.spellings
+            .iter()
+            .map(|(script, phoneme, spelling)| (*script, phoneme.as_str(), spelling.as_str()))
+    }
+
+    /// Writes out the phonemes in the script, taking the longest phoneme with a spelling at each
+    /// step, and keeping any character that isn't part of one.
+    #[must_use]
+    pub fn spell(&self, phonemes: &str, script: Script) -> String {
+        let mut spelled = String::new();
+        let mut rest = phonemes;
+        while let Some(c) = rest.chars().next() {
+            let longest = self
+                .spellings
+                .iter()
+                .filter(|(s, p, _)| *s == script && !p.is_empty() && rest.starts_with(p.as_str()))
+                .max_by_key(|(_, p, _)| p.len());
+            if let Some((_, phoneme, spelling)) = longest {
+                spelled.push_str(spelling);
+                rest = &rest[phoneme.len()..];
+            } else {
+                spelled.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        spelled
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod orthography_tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn orthography() -> Orthography {
+        Orthography::new()
+            .with_spelling(Script::Latin, "ʃ", "sh")
+            .with_spelling(Script::Latin, "ŋ", "ng")
+            .with_spelling(Script::Latin, "tʃ", "ch")
+            .with_spelling(Script::Cyrillic, "ʃ", "ш")
+            .with_spelling(Script::Cyrillic, "tʃ", "ч")
+            .with_spelling(Script::Cyrillic, "a", "а")
+            .with_spelling(Script::Cyrillic, "t", "т")
+    }
+
+    #[rstest]
+    #[case("ʃaŋ", Script::Latin, "shang")]
+    #[case("ʃaŋ", Script::Cyrillic, "шаŋ")]
+    #[case("tʃat", Script::Latin, "chat")]
+    #[case("tʃat", Script::Cyrillic, "чат")]
+    #[case("", Script::Latin, "")]
+    fn spell(#[case] phonemes: &str, #[case] script: Script, #[case] expected: &str) {
+        assert_eq!(orthography().spell(phonemes, script), expected);
+    }
+
+    #[test]
+    fn with_spelling__replaces() {
+        let orthography = orthography().with_spelling(Script::Latin, "ʃ", "sch");
+
+        assert_eq!(orthography.spelling(Script::Latin, "ʃ"), Some("sch"));
+        assert_eq!(orthography.spelling(Script::Cyrillic, "ʃ"), Some("ш"));
+        assert_eq!(orthography.spellings().count(), 7);
+    }
+
+    #[test]
+    fn extend() {
+        let mut parent = orthography();
+        let child = Orthography::new()
+            .with_spelling(Script::Latin, "ʃ", "x")
+            .with_spelling(Script::Latin, "ʒ", "zh");
+
+        parent.extend(&child);
+
+        assert_eq!(parent.spelling(Script::Latin, "ʃ"), Some("x"));
+        assert_eq!(parent.spelling(Script::Latin, "ʒ"), Some("zh"));
+        assert_eq!(parent.spelling(Script::Latin, "ŋ"), Some("ng"));
+    }
+
+    #[test]
+    fn scripts() {
+        assert_eq!(
+            orthography().scripts(),
+            vec![Script::Latin, Script::Cyrillic]
+        );
+        assert!(Orthography::new().scripts().is_empty());
+        assert!(Orthography::new().is_empty());
+    }
+}
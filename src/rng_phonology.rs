@@ -0,0 +1,394 @@
+use rand::distr::{Distribution, weighted::WeightedIndex};
+use rand::rngs::Xoshiro256PlusPlus;
+use rand::seq::{IndexedRandom, SliceRandom};
+use rand::{Rng, SeedableRng};
+use std::str::FromStr;
+
+use crate::rng_blend::usable;
+use crate::rng_syllable::Syllable;
+use crate::{LoadPolicy, RNG, RNGError, RNGErrorKind};
+
+const DEFAULT_SHAPES: &str = "CV CVC";
+const DEFAULT_SYLLABLES: usize = 24;
+
+/// How many times a syllable is drawn, for each one wanted, before settling for fewer.
+const ATTEMPTS: usize = 100;
+
+/// How many draws in a row can turn up nothing new before the sound system is taken to have run
+/// out of syllables. No fewer than the draws allowed for the default number of syllables.
+const STALE_DRAWS: usize = ATTEMPTS * DEFAULT_SYLLABLES;
+
+/// `Phonology` builds a brand new language out of a sound system: an inventory of consonants
+/// and vowels, the shapes its syllables take, such as `CV`, `CVC` and `CCV`, and any clusters
+/// of sounds it doesn't allow.
+///
+/// Each seed gives a different language from the same sound system. The seed decides how common
+/// each sound is, so that one language leans on `k` and `a` where the next prefers `t` and `o`,
+/// and which syllables turn up in each of the prefixes, centers and suffixes. Syllables are
+/// weighted by how often they were drawn, compared to the rarest one. Banned clusters are kept
+/// out of every syllable, though they can still form where two syllables meet.
+///
+/// Sounds are written as they should be spelled, one or more letters each, separated by spaces
+/// or commas. The result is an ordinary `RNG`, which can be written out with
+/// `RNG::to_language_file`.
+///
+/// ```
+/// use rnglib::Phonology;
+///
+/// let rng = Phonology::new("Coastal")
+///     .with_consonants("p t k m n s h l")
+///     .with_vowels("a i u")
+///     .with_shapes("CV CVC V")
+///     .banning("tl")
+///     .with_seed(42)
+///     .generate()
+///     .unwrap();
+///
+/// println!("{}: {}", rng.name, rng.generate_name());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Phonology {
+    name: String,
+    consonants: Vec<String>,
+    vowels: Vec<String>,
+    shapes: Vec<String>,
+    banned: Vec<String>,
+    seed: u64,
+    syllables: usize,
+}
+
+impl Phonology {
+    /// Starts an empty sound system, with a random seed, for a language that will be given the
+    /// name passed in.
+    #[must_use]
+    pub fn new(name: &str) -> Phonology {
+        Phonology {
+            name: name.to_string(),
+            consonants: Vec::new(),
+            vowels: Vec::new(),
+            shapes: Vec::new(),
+            banned: Vec::new(),
+            seed: rand::rng().next_u64(),
+            syllables: DEFAULT_SYLLABLES,
+        }
+    }
+
+    #[must_use]
+    pub fn with_consonants(mut self, consonants: &str) -> Phonology {
+        self.consonants
+            .extend(split(consonants).map(ToString::to_string));
+        self
+    }
+
+    #[must_use]
+    pub fn with_vowels(mut self, vowels: &str) -> Phonology {
+        self.vowels.extend(split(vowels).map(ToString::to_string));
+        self
+    }
+
+    /// Adds syllable shapes, written with `C` for a consonant and `V` for a vowel. Without any,
+    /// syllables are `CV` or `CVC`.
+    #[must_use]
+    pub fn with_shapes(mut self, shapes: &str) -> Phonology {
+        self.shapes.extend(split(shapes).map(ToString::to_string));
+        self
+    }
+
+    /// Keeps clusters of sounds, such as `tl`, out of the syllables.
+    #[must_use]
+    pub fn banning(mut self, clusters: &str) -> Phonology {
+        self.banned.extend(split(clusters).map(str::to_lowercase));
+        self
+    }
+
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Phonology {
+        self.seed = seed;
+        self
+    }
+
+    /// How many different syllables to aim for in each of the prefixes, centers and suffixes.
+    /// There may be fewer, if the sound system can't make that many. The default is 24.
+    #[must_use]
+    pub fn with_syllables(mut self, syllables: usize) -> Phonology {
+        self.syllables = syllables;
+        self
+    }
+
+    #[must_use]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Builds the language.
+    ///
+    /// # Errors
+    ///
+    /// Errors out with `RNGErrorKind::ParsingError` if a sound can't be written in a language
+    /// file, or `RNGErrorKind::GenerationError` if a shape isn't made of `C` and `V`, a shape
+    /// calls for consonants or vowels there are none of, or the language ends up without any
+    /// prefixes, centers or suffixes.
+    pub fn generate(&self) -> Result<RNG, RNGError> {
+        for sound in self.consonants.iter().chain(&self.vowels) {
+            if sound.starts_with(['-', '+']) || Syllable::from_str(sound).is_err() {
+                return Err(RNGError::new(RNGErrorKind::ParsingError).with_text(sound));
+            }
+        }
+        let shapes = self.shapes()?;
+
+        let mut random = Xoshiro256PlusPlus::seed_from_u64(self.seed);
+        let consonants = Inventory::new(&self.consonants, &mut random);
+        let vowels = Inventory::new(&self.vowels, &mut random);
+        for (inventory, slot, label) in [
+            (&consonants, true, "consonants"),
+            (&vowels, false, "vowels"),
+        ] {
+            if inventory.is_none() && shapes.iter().flatten().any(|s| *s == slot) {
+                return Err(RNGError::new(RNGErrorKind::GenerationError)
+                    .with_text(&format!("{} has no {label}", self.name)));
+            }
+        }
+
+        let mut file = String::new();
+        for classification in ["-", "", "+"] {
+            let mut pool: Vec<(String, u32)> = Vec::new();
+            let mut stale = 0;
+            for _ in 0..self.syllables.saturating_mul(ATTEMPTS) {
+                if pool.len() == self.syllables || stale == STALE_DRAWS {
+                    break;
+                }
+                stale += 1;
+                let Some(shape) = shapes.choose(&mut random) else {
+                    break;
+                };
+                let syllable: String = shape
+                    .iter()
+                    .map(|consonant| {
+                        let inventory = if *consonant { &consonants } else { &vowels };
+                        inventory.as_ref().map_or("", |i| i.pick(&mut random))
+                    })
+                    .collect();
+                let lowercase = syllable.to_lowercase();
+                if self.banned.iter().any(|b| lowercase.contains(b.as_str())) {
+                    continue;
+                }
+                if let Some((_, count)) = pool.iter_mut().find(|(s, _)| *s == syllable) {
+                    *count += 1;
+                } else {
+                    pool.push((syllable, 1));
+                    stale = 0;
+                }
+            }
+
+            let rarest = pool.iter().map(|(_, count)| *count).min().unwrap_or(1);
+            for (syllable, count) in pool {
+                file.push_str(classification);
+                file.push_str(&syllable);
+                let weight = (count + rarest / 2) / rarest;
+                if weight > 1 {
+                    file.push_str(" *");
+                    file.push_str(&weight.to_string());
+                }
+                file.push('\n');
+            }
+        }
+        // Pools left empty are reported by `usable`, after pruning.
+        usable(RNG::load(self.name.clone(), &file, LoadPolicy::Lenient)?.0)
+    }
+
+    /// The shapes, as a list of slots that are `true` for a consonant and `false` for a vowel.
+    fn shapes(&self) -> Result<Vec<Vec<bool>>, RNGError> {
+        let shapes: Vec<&str> = if self.shapes.is_empty() {
+            split(DEFAULT_SHAPES).collect()
+        } else {
+            self.shapes.iter().map(String::as_str).collect()
+        };
+        shapes
+            .into_iter()
+            .map(|shape| {
+                shape
+                    .chars()
+                    .map(|c| match c.to_ascii_uppercase() {
+                        'C' => Ok(true),
+                        'V' => Ok(false),
+                        _ => Err(RNGError::new(RNGErrorKind::GenerationError)
+                            .with_text(&format!("{shape} is not a syllable shape"))),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Consonants or vowels, each more common than the next, in an order that depends on the seed.
+struct Inventory<'a> {
+    sounds: Vec<&'a str>,
+    frequencies: WeightedIndex<f64>,
+}
+
+impl<'a> Inventory<'a> {
+    fn new<R: Rng + ?Sized>(sounds: &'a [String], rng: &mut R) -> Option<Inventory<'a>> {
+        let mut sounds: Vec<&str> = sounds.iter().map(String::as_str).collect();
+        sounds.shuffle(rng);
+        #[allow(clippy::cast_precision_loss)]
+        let frequencies =
+            WeightedIndex::new((1..=sounds.len()).map(|rank| 1.0 / rank as f64)).ok()?;
+        Some(Inventory {
+            sounds,
+            frequencies,
+        })
+    }
+
+    fn pick<R: Rng + ?Sized>(&self, rng: &mut R) -> &'a str {
+        self.sounds[self.frequencies.sample(rng)]
+    }
+}
+
+fn split(list: &str) -> impl Iterator<Item = &str> {
+    list.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod phonology_tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn phonology() -> Phonology {
+        Phonology::new("Coastal")
+            .with_consonants("p t k m n s h l")
+            .with_vowels("a, i, u")
+            .with_shapes("CV CVC V")
+            .with_seed(42)
+    }
+
+    /// The shape of a syllable made of single letter sounds.
+    fn shape(syllable: &str) -> String {
+        syllable
+            .chars()
+            .map(|c| if "aiu".contains(c) { 'V' } else { 'C' })
+            .collect()
+    }
+
+    #[test]
+    fn generate() {
+        let rng = phonology().generate().unwrap();
+
+        assert_eq!(rng.name, "Coastal");
+        for syllable in rng.syllables().all() {
+            assert!(
+                ["CV", "CVC", "V"].contains(&shape(&syllable.value).as_str()),
+                "{syllable}"
+            );
+            assert!(syllable.value.chars().all(|c| "ptkmnshlaiu".contains(c)));
+        }
+        assert!(!rng.generate_name().is_empty());
+    }
+
+    #[test]
+    fn generate__same_seed_same_language() {
+        assert_eq!(
+            phonology().generate().unwrap(),
+            phonology().generate().unwrap()
+        );
+        assert_ne!(
+            phonology().generate().unwrap(),
+            phonology().with_seed(43).generate().unwrap()
+        );
+    }
+
+    #[test]
+    fn generate__bans_clusters() {
+        let rng = phonology().banning("pa ki").generate().unwrap();
+
+        for syllable in rng.syllables().all() {
+            assert!(!syllable.value.contains("pa"), "{syllable}");
+            assert!(!syllable.value.contains("ki"), "{syllable}");
+        }
+    }
+
+    #[test]
+    fn generate__multiple_letter_sounds() {
+        let rng = Phonology::new("Hissing")
+            .with_consonants("sh th")
+            .with_vowels("ee")
+            .with_shapes("CV")
+            .generate()
+            .unwrap();
+
+        let mut values: Vec<&str> = rng
+            .prefixes
+            .all()
+            .iter()
+            .map(|s| s.value.as_str())
+            .collect();
+        values.sort_unstable();
+
+        assert_eq!(values, vec!["shee", "thee"]);
+    }
+
+    #[test]
+    fn generate__syllables() {
+        let rng = phonology().with_syllables(5).generate().unwrap();
+
+        assert_eq!(rng.prefixes.len(), 5);
+        assert_eq!(rng.centers.len(), 5);
+        assert_eq!(rng.suffixes.len(), 5);
+    }
+
+    #[test]
+    fn generate__more_syllables_than_can_be_made() {
+        let rng = Phonology::new("Tiny")
+            .with_consonants("k t")
+            .with_vowels("a")
+            .with_shapes("CV")
+            .with_syllables(usize::MAX)
+            .generate()
+            .unwrap();
+
+        assert_eq!(rng.prefixes.len(), 2);
+        assert_eq!(rng.suffixes.len(), 2);
+    }
+
+    #[test]
+    fn generate__default_shapes() {
+        let rng = Phonology::new("Plain")
+            .with_consonants("p t k")
+            .with_vowels("a i")
+            .generate()
+            .unwrap();
+
+        for syllable in rng.syllables().all() {
+            assert!(["CV", "CVC"].contains(&shape(&syllable.value).as_str()));
+        }
+    }
+
+    #[rstest]
+    #[case(phonology().with_shapes("CVX"), RNGErrorKind::GenerationError, "CVX is not a syllable shape")]
+    #[case(Phonology::new("Mute").with_consonants("p t"), RNGErrorKind::GenerationError, "Mute has no vowels")]
+    #[case(Phonology::new("Banned").with_consonants("k").with_vowels("a").with_shapes("CV").banning("ka"), RNGErrorKind::GenerationError, "Banned has no usable prefix syllables")]
+    #[case(phonology().with_vowels("a1"), RNGErrorKind::ParsingError, "a1")]
+    #[case(phonology().with_consonants("-p"), RNGErrorKind::ParsingError, "-p")]
+    fn generate__invalid(
+        #[case] phonology: Phonology,
+        #[case] kind: RNGErrorKind,
+        #[case] text: &str,
+    ) {
+        let error = phonology.generate().unwrap_err();
+
+        assert_eq!(error.kind(), kind);
+        assert_eq!(error.text(), Some(text));
+    }
+
+    #[test]
+    fn generate__vowels_only() {
+        let rng = Phonology::new("Open")
+            .with_vowels("a e o")
+            .with_shapes("V")
+            .generate()
+            .unwrap();
+
+        assert_eq!(rng.prefixes.len(), 3);
+    }
+}
@@ -0,0 +1,90 @@
+use sha2::{Digest, Sha256};
+
+/// The SHA-256 block size, in bytes.
+const BLOCK_SIZE: usize = 64;
+
+/// The seed for the pseudonym of `input`: its HMAC-SHA256 under the secret `key`. Without the key,
+/// there is no telling which input a pseudonym was generated from.
+pub(crate) fn seed(key: &[u8], input: &str) -> [u8; 32] {
+    hmac(key, input.as_bytes())
+}
+
+/// The number a `NameEncoder` turns into the pseudonym of `input`: the first eight bytes of its
+/// HMAC-SHA256 under the secret `key`. Two inputs only share a number if their HMACs share those
+/// eight bytes, which takes around four billion inputs before it is likely to happen once.
+pub(crate) fn number(key: &[u8], input: &str) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hmac(key, input.as_bytes())[..8]);
+    u64::from_be_bytes(bytes)
+}
+
+/// HMAC-SHA256, as defined in RFC 2104.
+fn hmac(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let inner = Sha256::new()
+        .chain_update(block.map(|b| b ^ 0x36))
+        .chain_update(message)
+        .finalize();
+    Sha256::new()
+        .chain_update(block.map(|b| b ^ 0x5c))
+        .chain_update(inner)
+        .finalize()
+        .into()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod pseudonym_tests {
+    use super::*;
+    use rstest::rstest;
+    use std::fmt::Write;
+
+    fn hex(bytes: &[u8]) -> String {
+        let mut s = String::new();
+        for byte in bytes {
+            write!(s, "{byte:02x}").unwrap();
+        }
+        s
+    }
+
+    // Test cases 1, 2 and 6 from RFC 4231.
+    #[rstest]
+    #[case(
+        &[0x0b; 20],
+        b"Hi There",
+        "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+    )]
+    #[case(
+        b"Jefe",
+        b"what do ya want for nothing?",
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    )]
+    #[case(
+        &[0xaa; 131],
+        b"Test Using Larger Than Block-Size Key - Hash Key First",
+        "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+    )]
+    fn hmac__rfc_4231(#[case] key: &[u8], #[case] message: &[u8], #[case] expected: &str) {
+        assert_eq!(hex(&hmac(key, message)), expected);
+    }
+
+    #[test]
+    fn seed__depends_on_key_and_input() {
+        assert_eq!(seed(b"key", "player-1"), seed(b"key", "player-1"));
+        assert_ne!(seed(b"key", "player-1"), seed(b"key", "player-2"));
+        assert_ne!(seed(b"key", "player-1"), seed(b"other", "player-1"));
+    }
+
+    #[test]
+    fn number__is_the_start_of_the_seed() {
+        let seed = seed(b"key", "player-1");
+
+        assert_eq!(number(b"key", "player-1").to_be_bytes(), seed[..8]);
+    }
+}
@@ -0,0 +1,508 @@
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::rng_compiled::EXTENSION as COMPILED_EXTENSION;
+use crate::{Identification, Language, LoadPolicy, RNG, RNGError, RNGErrorKind};
+
+/// Environment variable holding extra language directories, separated like `PATH`.
+pub const LANGUAGES_PATH_ENV: &str = "RNG_LANGUAGES_PATH";
+
+const TEXT_EXTENSION: &str = "txt";
+
+/// Where a language known to a `LanguageRegistry` comes from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LanguageSource {
+    /// One of the languages embedded in the library.
+    BuiltIn(Language),
+    /// A text or compiled language file on disk.
+    File(PathBuf),
+}
+
+impl fmt::Display for LanguageSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LanguageSource::BuiltIn(_) => write!(f, "built-in"),
+            LanguageSource::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// A language that can be looked up by name.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LanguageEntry {
+    pub name: String,
+    pub source: LanguageSource,
+}
+
+impl LanguageEntry {
+    /// Loads the language. The resulting RNG is named after the entry, rather than the file it
+    /// came from. A language file that `@extends` another language by name finds it through
+    /// `LanguageRegistry::from_env`; see `load_with`.
+    ///
+    /// # Errors
+    ///
+    /// Errors out if the language file is not able to be processed correctly.
+    pub fn load(&self) -> Result<RNG, RNGError> {
+        self.load_with(&LanguageRegistry::from_env())
+    }
+
+    /// Loads the language like `load`, looking up the languages it `@extends` by name in the
+    /// registry, which is usually the one the entry was found in.
+    ///
+    /// # Errors
+    ///
+    /// Errors out if the language file is not able to be processed correctly.
+    pub fn load_with(&self, registry: &LanguageRegistry) -> Result<RNG, RNGError> {
+        let mut rng = match &self.source {
+            LanguageSource::BuiltIn(language) => RNG::new(language)?,
+            LanguageSource::File(path) => {
+                let file = path.to_string_lossy().to_string();
+                RNG::load_path(path, file, LoadPolicy::Strict, Vec::new(), registry)?.0
+            }
+        };
+        rng.name.clone_from(&self.name);
+        Ok(rng)
+    }
+}
+
+/// `LanguageRegistry` finds languages by name, looking through a list of directories before
+/// falling back to the built-in languages. A language's name is its file name without the
+/// extension, matched ignoring case, hyphens, underscores and spaces, like `Language::from_str`,
+/// so `~/.local/share/rng/languages/elven.txt` is found as `Elven` and shadows the built-in one,
+/// and `german_curse` finds `German-curse`.
+///
+/// Directories are searched in order, and the first match wins. Within a directory, both text
+/// (`.txt`) and compiled (`.rngc`) language files are picked up; a compiled file takes precedence
+/// over a text file with the same name.
+///
+/// ```
+/// use rnglib::LanguageRegistry;
+///
+/// let registry = LanguageRegistry::from_env();
+/// let rng = registry.load("Elven").unwrap();
+///
+/// println!("{}: {}", rng.name, rng.generate_name());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LanguageRegistry {
+    directories: Vec<PathBuf>,
+}
+
+impl LanguageRegistry {
+    /// A registry that only knows about the built-in languages.
+    #[must_use]
+    pub fn new() -> LanguageRegistry {
+        LanguageRegistry::default()
+    }
+
+    /// A registry that searches the directories listed in `$RNG_LANGUAGES_PATH`, then
+    /// `$XDG_DATA_HOME/rng/languages` (defaulting to `~/.local/share/rng/languages`).
+    #[must_use]
+    pub fn from_env() -> LanguageRegistry {
+        LanguageRegistry {
+            directories: LanguageRegistry::default_directories(
+                std::env::var_os(LANGUAGES_PATH_ENV),
+                std::env::var_os("XDG_DATA_HOME"),
+                std::env::var_os("HOME"),
+            ),
+        }
+    }
+
+    fn default_directories(
+        languages_path: Option<OsString>,
+        xdg_data_home: Option<OsString>,
+        home: Option<OsString>,
+    ) -> Vec<PathBuf> {
+        let mut directories: Vec<PathBuf> = languages_path
+            .map(|paths| std::env::split_paths(&paths).collect())
+            .unwrap_or_default();
+
+        let data_home = match xdg_data_home.filter(|s| !s.is_empty()) {
+            Some(xdg) => Some(PathBuf::from(xdg)),
+            None => home.map(|home| Path::new(&home).join(".local").join("share")),
+        };
+        if let Some(data_home) = data_home {
+            directories.push(data_home.join("rng").join("languages"));
+        }
+
+        directories.retain(|d| !d.as_os_str().is_empty());
+        directories
+    }
+
+    /// Adds a directory to search after the ones already configured.
+    #[must_use]
+    pub fn with_directory(mut self, directory: impl Into<PathBuf>) -> LanguageRegistry {
+        self.directories.push(directory.into());
+        self
+    }
+
+    #[must_use]
+    pub fn directories(&self) -> &[PathBuf] {
+        &self.directories
+    }
+
+    /// Every language available, sorted by name. Languages in the configured directories shadow
+    /// built-in languages with the same name.
+    #[must_use]
+    pub fn entries(&self) -> Vec<LanguageEntry> {
+        let mut entries: Vec<LanguageEntry> = Vec::new();
+        for entry in self.file_entries().into_iter().chain(built_in_entries()) {
+            if !entries.iter().any(|e| same_name(&e.name, &entry.name)) {
+                entries.push(entry);
+            }
+        }
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        entries
+    }
+
+    /// Looks a language up by name, case-insensitively.
+    #[must_use]
+    pub fn find(&self, name: &str) -> Option<LanguageEntry> {
+        self.file_entries()
+            .into_iter()
+            .chain(built_in_entries())
+            .find(|entry| same_name(&entry.name, name))
+    }
+
+    /// Looks a language up by name and loads it, see `LanguageEntry::load_with`.
+    ///
+    /// # Errors
+    ///
+    /// Errors out with `RNGErrorKind::UnknownLanguage` if there is no language with that name, or
+    /// if the language file is not able to be processed correctly.
+    pub fn load(&self, name: &str) -> Result<RNG, RNGError> {
+        self.find(name)
+            .ok_or_else(|| RNGError::new(RNGErrorKind::UnknownLanguage).with_text(name))?
+            .load_with(self)
+    }
+
+    /// Loads every language available, in the order of `entries`. Languages that fail to load
+    /// are returned separately, along with why, rather than stopping the rest from loading.
+    #[must_use]
+    pub fn load_all(&self) -> (Vec<RNG>, Vec<(LanguageEntry, RNGError)>) {
+        let mut languages = Vec::new();
+        let mut failed = Vec::new();
+        for entry in self.entries() {
+            match entry.load_with(self) {
+                Ok(rng) => languages.push(rng),
+                Err(error) => failed.push((entry, error)),
+            }
+        }
+        (languages, failed)
+    }
+
+    /// Scores the name against every language available, most likely first, leaving out the
+    /// ones that couldn't have made it. See `RNG::identify`.
+    ///
+    /// Languages that fail to load are skipped; use `load_all` to find out which ones those are.
+    #[must_use]
+    pub fn identify(&self, name: &str) -> Vec<Identification> {
+        RNG::identify(name, &self.load_all().0)
+    }
+
+    /// Language files found in the configured directories, in search order. Directories that
+    /// don't exist or can't be read are skipped.
+    fn file_entries(&self) -> Vec<LanguageEntry> {
+        let mut entries: Vec<LanguageEntry> = Vec::new();
+        for directory in &self.directories {
+            let Ok(dir) = std::fs::read_dir(directory) else {
+                continue;
+            };
+            let mut found: Vec<PathBuf> = dir
+                .filter_map(Result::ok)
+                .map(|e| e.path())
+                .filter(|p| p.is_file() && language_extension(p).is_some())
+                .collect();
+            // Compiled files sort ahead of text files with the same name, so they win.
+            found.sort_by_key(|p| (p.file_stem().map(ToOwned::to_owned), language_extension(p)));
+
+            for path in found {
+                let Some(name) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+                    continue;
+                };
+                if !entries.iter().any(|e| same_name(&e.name, &name)) {
+                    entries.push(LanguageEntry {
+                        name,
+                        source: LanguageSource::File(path),
+                    });
+                }
+            }
+        }
+        entries
+    }
+}
+
+/// Ranks the supported language file extensions; lower is preferred.
+fn language_extension(path: &Path) -> Option<u8> {
+    match path.extension()?.to_str()? {
+        COMPILED_EXTENSION => Some(0),
+        TEXT_EXTENSION => Some(1),
+        _ => None,
+    }
+}
+
+fn built_in_entries() -> Vec<LanguageEntry> {
+    Language::all()
+        .iter()
+        .map(|language| LanguageEntry {
+            name: language.to_string(),
+            source: LanguageSource::BuiltIn(*language),
+        })
+        .collect()
+}
+
+/// True if the names are the same, ignoring case, hyphens, underscores and spaces, so that
+/// `german_curse` finds `German-curse`.
+pub(crate) fn same_name(a: &str, b: &str) -> bool {
+    normalize_name(a) == normalize_name(b)
+}
+
+/// The name in lowercase, without hyphens, underscores or spaces.
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '-' | '_' | ' '))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod registry_tests {
+    use super::*;
+    use std::str::FromStr;
+
+    /// A scratch language directory that is removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(label: &str) -> TempDir {
+            let path =
+                std::env::temp_dir().join(format!("rng-registry-{label}-{}", std::process::id()));
+            std::fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+
+        fn write(&self, filename: &str, contents: &[u8]) {
+            std::fs::write(self.0.join(filename), contents).unwrap();
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn load__built_in() {
+        let rng = LanguageRegistry::new().load("Elven").unwrap();
+
+        assert_eq!(rng, RNG::try_from(&Language::Elven).unwrap());
+    }
+
+    #[test]
+    fn load__case_insensitive() {
+        let registry = LanguageRegistry::new();
+
+        assert_eq!(registry.load("goblin").unwrap().name, "Goblin");
+        assert_eq!(registry.load("гоблин").unwrap().name, "Гоблин");
+        assert_eq!(registry.load("german-curse").unwrap().name, "German-curse");
+    }
+
+    #[test]
+    fn find__same_names_as_from_str() {
+        let registry = LanguageRegistry::new();
+
+        for name in ["german_curse", "GERMAN CURSE", "germancurse"] {
+            assert_eq!(
+                registry.find(name).map(|entry| entry.source),
+                Some(LanguageSource::BuiltIn(Language::GermanCurse)),
+                "{name}"
+            );
+            assert_eq!(Language::from_str(name), Ok(Language::GermanCurse));
+        }
+    }
+
+    #[test]
+    fn load__unknown() {
+        let error = LanguageRegistry::new().load("Vulcan").unwrap_err();
+
+        assert_eq!(error.kind(), RNGErrorKind::UnknownLanguage);
+        assert_eq!(error.to_string(), "unknown language: \"Vulcan\"");
+    }
+
+    #[test]
+    fn load__user_directory() {
+        let dir = TempDir::new("user");
+        dir.write("Orcish.txt", b"-gr\nu\n+k");
+        dir.write("notes.md", b"not a language");
+
+        let registry = LanguageRegistry::new().with_directory(&dir.0);
+        let rng = registry.load("orcish").unwrap();
+
+        assert_eq!(rng.name, "Orcish");
+        assert_eq!(rng.syllables().len(), 3);
+        assert!(registry.find("notes").is_none());
+    }
+
+    #[test]
+    fn identify() {
+        let dir = TempDir::new("identify");
+        dir.write("Orcish.txt", b"-gr\nu\n+k");
+
+        let registry = LanguageRegistry::new().with_directory(&dir.0);
+        let found = registry.identify("Gruk");
+
+        assert_eq!(found[0].language, "Orcish");
+        assert!(LanguageRegistry::new().identify("Gruk").is_empty());
+    }
+
+    #[test]
+    fn identify__skips_broken_languages() {
+        let dir = TempDir::new("identify_broken");
+        dir.write("Orcish.txt", b"-gr\nu\n+k");
+        dir.write("Broken.txt", b"-gr\n!!");
+
+        let registry = LanguageRegistry::new().with_directory(&dir.0);
+        let (languages, failed) = registry.load_all();
+
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0.name, "Broken");
+        assert!(languages.iter().any(|rng| rng.name == "Orcish"));
+        assert_eq!(registry.identify("Gruk")[0].language, "Orcish");
+    }
+
+    #[test]
+    fn load__user_file_shadows_built_in() {
+        let dir = TempDir::new("shadow");
+        dir.write("elven.txt", b"-a\nb\n+c");
+
+        let registry = LanguageRegistry::new().with_directory(&dir.0);
+        let rng = registry.load("Elven").unwrap();
+
+        assert_eq!(rng.name, "elven");
+        assert_eq!(rng.generate_name_by_count(3), "Abc");
+        assert_eq!(
+            registry.find("Elven").unwrap().source,
+            LanguageSource::File(dir.0.join("elven.txt"))
+        );
+    }
+
+    #[test]
+    fn load__user_file_extends_the_built_in_it_shadows() {
+        let dir = TempDir::new("extends");
+        dir.write("Elven.txt", b"@extends Elven\n-zyr");
+
+        let rng = LanguageRegistry::new()
+            .with_directory(&dir.0)
+            .load("Elven")
+            .unwrap();
+        let elven = RNG::try_from(&Language::Elven).unwrap();
+
+        assert_eq!(rng.prefixes.len(), elven.prefixes.len() + 1);
+        assert_eq!(rng.suffixes, elven.suffixes);
+    }
+
+    #[test]
+    fn load__user_file_extends_a_sibling() {
+        let dir = TempDir::new("sibling");
+        dir.write("Base.txt", b"-ka\nri\n+zu");
+        dir.write("Child.txt", b"@extends Base\n-zo");
+
+        let rng = LanguageRegistry::new()
+            .with_directory(&dir.0)
+            .load("Child")
+            .unwrap();
+
+        assert_eq!(rng.prefixes.len(), 2);
+        assert_eq!(rng.suffixes.len(), 1);
+    }
+
+    #[test]
+    fn load__compiled_preferred_over_text() {
+        let dir = TempDir::new("compiled");
+        dir.write("Micro.txt", b"-x\ny\n+z");
+        let compiled = RNG::new_from_str("Micro".to_string(), "-a\nb\n+c").unwrap();
+        dir.write("Micro.rngc", &compiled.compile().unwrap());
+
+        let rng = LanguageRegistry::new()
+            .with_directory(&dir.0)
+            .load("Micro")
+            .unwrap();
+
+        assert_eq!(rng, compiled);
+    }
+
+    #[test]
+    fn load__first_directory_wins() {
+        let first = TempDir::new("first");
+        let second = TempDir::new("second");
+        first.write("Orcish.txt", b"-a\nb\n+c");
+        second.write("Orcish.txt", b"-x\ny\n+z");
+
+        let rng = LanguageRegistry::new()
+            .with_directory(&first.0)
+            .with_directory(&second.0)
+            .load("Orcish")
+            .unwrap();
+
+        assert_eq!(rng.generate_name_by_count(3), "Abc");
+    }
+
+    #[test]
+    fn entries() {
+        let dir = TempDir::new("entries");
+        dir.write("Elven.txt", b"-a\nb\n+c");
+        dir.write("Orcish.txt", b"-a\nb\n+c");
+
+        let entries = LanguageRegistry::new().with_directory(&dir.0).entries();
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+
+        assert!(names.contains(&"Orcish"));
+        assert!(names.contains(&"Goblin"));
+        assert_eq!(names.iter().filter(|n| **n == "Elven").count(), 1);
+        assert_eq!(
+            entries.iter().find(|e| e.name == "Elven").unwrap().source,
+            LanguageSource::File(dir.0.join("Elven.txt"))
+        );
+    }
+
+    #[test]
+    fn entries__missing_directory_is_skipped() {
+        let registry = LanguageRegistry::new().with_directory("/no/such/rng/directory");
+
+        assert_eq!(registry.entries(), LanguageRegistry::new().entries());
+    }
+
+    #[test]
+    fn default_directories() {
+        let directories = LanguageRegistry::default_directories(
+            Some(OsString::from("/opt/rng")),
+            Some(OsString::from("/data")),
+            Some(OsString::from("/home/me")),
+        );
+
+        assert_eq!(
+            directories,
+            vec![
+                PathBuf::from("/opt/rng"),
+                PathBuf::from("/data/rng/languages")
+            ]
+        );
+    }
+
+    #[test]
+    fn default_directories__home_fallback() {
+        let directories = LanguageRegistry::default_directories(
+            None,
+            Some(OsString::new()),
+            Some(OsString::from("/home/me")),
+        );
+
+        assert_eq!(
+            directories,
+            vec![PathBuf::from("/home/me/.local/share/rng/languages")]
+        );
+    }
+}
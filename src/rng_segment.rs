@@ -0,0 +1,333 @@
+use crate::RNG;
+use crate::rng_joiner::Joiner;
+use crate::rng_syllable::Syllable;
+
+/// One way of splitting a name into a language's syllables, returned by `RNG::segment`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Segmentation {
+    /// The syllables, as they are spelled in the language.
+    pub syllables: Vec<String>,
+    /// The chance that the language picks exactly these syllables when it generates a name with
+    /// this many syllables.
+    pub probability: f64,
+}
+
+/// The most segmentations `segment` returns. A name can be split in exponentially many ways, such
+/// as a long run of `a`s in a language with both `a` and `aa` centers.
+pub(crate) const MAX_SEGMENTATIONS: usize = 1000;
+
+/// Every way of splitting the name, ignoring case, into a prefix, any number of centers and a
+/// suffix of the language that join up with each other, most likely first, up to
+/// `MAX_SEGMENTATIONS` of them.
+pub(crate) fn segment<'a>(language: &'a RNG, name: &str) -> Vec<(Vec<&'a Syllable>, f64)> {
+    let name = name.to_lowercase();
+    let pools = Pools::new(language);
+    let finishes = pools.finishes(&name);
+    let mut found = Vec::new();
+    for choice in &pools.prefixes {
+        if found.len() == MAX_SEGMENTATIONS {
+            break;
+        }
+        if let Some(rest) = name.strip_prefix(choice.value.as_str()) {
+            let mut syllables = vec![choice.syllable];
+            walk(
+                &pools,
+                &finishes,
+                &name,
+                rest,
+                &mut syllables,
+                choice.chance,
+                &mut found,
+            );
+        }
+    }
+    found.sort_by(|a, b| b.1.total_cmp(&a.1));
+    found
+}
+
+/// Finishes the name from the syllables so far, which leave `rest` of it to account for, taking
+/// only the turns that `finishes` says lead somewhere.
+fn walk<'a>(
+    pools: &Pools<'a>,
+    finishes: &[[bool; 16]],
+    name: &str,
+    rest: &str,
+    syllables: &mut Vec<&'a Syllable>,
+    probability: f64,
+    found: &mut Vec<(Vec<&'a Syllable>, f64)>,
+) {
+    let last = syllables[syllables.len() - 1].jnext;
+    if !finishes[name.len() - rest.len()][usize::from(last.bits())] {
+        return;
+    }
+    for choice in pools.suffixes_after(last) {
+        if found.len() == MAX_SEGMENTATIONS {
+            return;
+        }
+        if rest == choice.value {
+            let mut done = syllables.clone();
+            done.push(choice.syllable);
+            found.push((done, probability * choice.chance));
+        }
+    }
+    for choice in pools.centers_after(last) {
+        if found.len() == MAX_SEGMENTATIONS {
+            return;
+        }
+        if let Some(after) = rest
+            .strip_prefix(choice.value.as_str())
+            .filter(|after| !after.is_empty())
+        {
+            syllables.push(choice.syllable);
+            let chance = probability * choice.chance;
+            walk(pools, finishes, name, after, syllables, chance, found);
+            syllables.pop();
+        }
+    }
+}
+
+/// A syllable that can be picked, with its lowercase value and the chance of it being picked.
+pub(crate) struct Choice<'a> {
+    pub(crate) syllable: &'a Syllable,
+    pub(crate) value: String,
+    pub(crate) chance: f64,
+}
+
+/// The choices a language offers, worked out once for each pool and, for centers and suffixes,
+/// for each joiner the syllable before can end with.
+pub(crate) struct Pools<'a> {
+    pub(crate) prefixes: Vec<Choice<'a>>,
+    centers: Vec<Vec<Choice<'a>>>,
+    suffixes: Vec<Vec<Choice<'a>>>,
+}
+
+impl<'a> Pools<'a> {
+    pub(crate) fn new(language: &'a RNG) -> Pools<'a> {
+        let after_every_joiner = |pool: &'a [Syllable]| -> Vec<Vec<Choice<'a>>> {
+            let grouped = grouped(pool);
+            (0u8..16)
+                .map(|bits| choices(&grouped, Some(Joiner::from_bits_retain(bits))))
+                .collect()
+        };
+        Pools {
+            prefixes: choices(&grouped(language.prefixes.all()), None),
+            centers: after_every_joiner(language.centers.all()),
+            suffixes: after_every_joiner(language.suffixes.all()),
+        }
+    }
+
+    pub(crate) fn centers_after(&self, last: Joiner) -> &[Choice<'a>] {
+        &self.centers[usize::from(last.bits())]
+    }
+
+    pub(crate) fn suffixes_after(&self, last: Joiner) -> &[Choice<'a>] {
+        &self.suffixes[usize::from(last.bits())]
+    }
+
+    /// Whether the lowercase name can be finished from each byte offset, after a syllable ending
+    /// with each joiner, by any number of centers and a suffix.
+    pub(crate) fn finishes(&self, name: &str) -> Vec<[bool; 16]> {
+        let mut finishes = vec![[false; 16]; name.len() + 1];
+        for offset in (0..name.len()).rev() {
+            if !name.is_char_boundary(offset) {
+                continue;
+            }
+            let rest = &name[offset..];
+            for bits in 0u8..16 {
+                let last = Joiner::from_bits_retain(bits);
+                finishes[offset][usize::from(bits)] = self
+                    .suffixes_after(last)
+                    .iter()
+                    .any(|choice| rest == choice.value)
+                    || self.centers_after(last).iter().any(|choice| {
+                        rest.strip_prefix(choice.value.as_str())
+                            .filter(|after| !after.is_empty())
+                            .is_some_and(|after| {
+                                finishes[name.len() - after.len()]
+                                    [usize::from(choice.syllable.jnext.bits())]
+                            })
+                    });
+            }
+        }
+        finishes
+    }
+}
+
+/// Each distinct syllable in the pool, with the summed weight of its repeats.
+fn grouped(pool: &[Syllable]) -> Vec<(&Syllable, u64)> {
+    let mut grouped: Vec<(&Syllable, u64)> = Vec::new();
+    for syllable in pool {
+        match grouped.iter_mut().find(|(s, _)| {
+            s.value == syllable.value
+                && s.jprevious == syllable.jprevious
+                && s.jnext == syllable.jnext
+        }) {
+            Some((_, weight)) => *weight += u64::from(syllable.weight),
+            None => grouped.push((syllable, u64::from(syllable.weight))),
+        }
+    }
+    grouped
+}
+
+/// The syllables that can follow a syllable ending with the joiner, or any of them if there is
+/// nothing before, each with the chance of it being picked. Empty syllables are left out, since
+/// they can't account for any of a name.
+fn choices<'a>(grouped: &[(&'a Syllable, u64)], last: Option<Joiner>) -> Vec<Choice<'a>> {
+    let compatible: Vec<&(&Syllable, u64)> = grouped
+        .iter()
+        .filter(|(s, _)| last.is_none_or(|last| last.joins(s.jprevious)))
+        .collect();
+    let total: u64 = compatible.iter().map(|(_, weight)| weight).sum();
+    if total == 0 {
+        return Vec::new();
+    }
+    compatible
+        .into_iter()
+        .filter(|(s, _)| !s.value.is_empty())
+        .map(|(syllable, weight)| {
+            #[allow(clippy::cast_precision_loss)]
+            let chance = *weight as f64 / total as f64;
+            Choice {
+                syllable,
+                value: syllable.value.to_lowercase(),
+                chance,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod segment_tests {
+    use super::*;
+    use crate::{Language, LoadPolicy};
+    use rstest::rstest;
+
+    fn values(syllables: &[&Syllable]) -> Vec<String> {
+        syllables.iter().map(|s| s.value.clone()).collect()
+    }
+
+    #[test]
+    fn segment__every_split() {
+        let rng = RNG::new_from_str("Micro".to_string(), "-ka\n-kar\nri\nir\n+izu\n+zu").unwrap();
+
+        let found = segment(&rng, "Karirizu");
+
+        let splits: Vec<Vec<String>> = found.iter().map(|(s, _)| values(s)).collect();
+        assert_eq!(splits.len(), 2);
+        assert!(splits.contains(&vec!["ka".into(), "ri".into(), "ri".into(), "zu".into()]));
+        assert!(splits.contains(&vec!["kar".into(), "ir".into(), "izu".into()]));
+    }
+
+    #[test]
+    fn segment__probabilities() {
+        let rng = RNG::new_from_str("Micro".to_string(), "-ka *3\n-ri\nmo\n+zu\n+do").unwrap();
+
+        let found = segment(&rng, "kamozu");
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(values(&found[0].0), vec!["ka", "mo", "zu"]);
+        assert!((found[0].1 - 0.75 * 1.0 * 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn segment__most_likely_first() {
+        let rng = RNG::load(
+            "Micro".to_string(),
+            "-ka\n-kaz *5\n+zu\n+u",
+            LoadPolicy::Lenient,
+        )
+        .unwrap()
+        .0;
+
+        let found = segment(&rng, "Kazu");
+
+        assert_eq!(found.len(), 2);
+        assert_eq!(values(&found[0].0), vec!["kaz", "u"]);
+        assert!(found[0].1 > found[1].1);
+    }
+
+    #[test]
+    fn segment__obeys_joiners() {
+        let rng = RNG::load(
+            "Micro".to_string(),
+            "-ka +v\n-kar\n+tu\n+u -v\n+otu",
+            LoadPolicy::Lenient,
+        )
+        .unwrap()
+        .0;
+
+        assert!(segment(&rng, "katu").is_empty());
+        assert!(segment(&rng, "karu").is_empty());
+        assert_eq!(values(&segment(&rng, "kaotu")[0].0), vec!["ka", "otu"]);
+    }
+
+    #[test]
+    fn segment__repeated_syllables_add_up() {
+        let rng = RNG::load(
+            "Micro".to_string(),
+            "-ka\n-ka\n-ri\n+zu",
+            LoadPolicy::Lenient,
+        )
+        .unwrap()
+        .0;
+
+        let found = segment(&rng, "kazu");
+
+        assert_eq!(found.len(), 1);
+        assert!((found[0].1 - 2.0 / 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn segment__capped() {
+        let rng = RNG::new_from_str("Runs".to_string(), "-ka\na\naa\n+zu").unwrap();
+        let name = format!("ka{}zu", "a".repeat(60));
+
+        let found = segment(&rng, &name);
+
+        assert_eq!(found.len(), MAX_SEGMENTATIONS);
+        assert!(found.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    }
+
+    #[test]
+    fn segment__dead_ends_are_skipped() {
+        let rng = RNG::new_from_str("Runs".to_string(), "-ka\na\naa\n+zu").unwrap();
+        let name = format!("ka{}zo", "a".repeat(60));
+
+        assert!(segment(&rng, &name).is_empty());
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("Ka")]
+    #[case("Xyzzy")]
+    #[case("kazuq")]
+    fn segment__not_a_name(#[case] name: &str) {
+        let rng = RNG::new_from_str("Micro".to_string(), "-ka\nri\n+zu").unwrap();
+
+        assert!(segment(&rng, name).is_empty());
+    }
+
+    #[rstest]
+    #[case(Language::Elven)]
+    #[case(Language::Fantasy)]
+    #[case(Language::Goblin)]
+    #[case(Language::Roman)]
+    #[case(Language::Гоблин)]
+    fn segment__generated_names(#[case] language: Language) {
+        let rng = RNG::try_from(&language).unwrap();
+
+        for _ in 0..50 {
+            let syllables = rng.generate_syllables();
+            let found = segment(&rng, &syllables.collapse());
+
+            assert!(
+                found.iter().any(|(s, probability)| {
+                    values(s) == values(&syllables.all().iter().collect::<Vec<_>>())
+                        && *probability > 0.0
+                }),
+                "{syllables:?}"
+            );
+        }
+    }
+}
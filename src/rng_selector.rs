@@ -0,0 +1,241 @@
+use rand::Rng;
+use rand::distr::{Distribution, weighted::WeightedIndex};
+use std::path::PathBuf;
+
+use crate::rng_registry::same_name;
+use crate::{Language, LanguageEntry, LanguageSource, RNG, RNGError, RNGErrorKind};
+
+/// `LanguageSelector` picks a language at random, each one in proportion to its weight. The
+/// choices can be any mix of built-in languages and language files, and nothing is loaded until a
+/// language has been picked.
+///
+/// The default selector offers every production-ready built-in language with a weight of 1, one
+/// variant per family, in `DEFAULT_LOCALE`: `Goblin`, but not `Гоблин` as well. Resolve the
+/// chosen language with `Language::for_locale` to use another locale.
+///
+/// ```
+/// use rnglib::{Language, LanguageSelector};
+///
+/// let selector = LanguageSelector::new()
+///     .with_language(Language::Elven, 3)
+///     .with_language(Language::Goblin, 1);
+///
+/// // Elven three times out of four.
+/// let rng = selector.random().unwrap();
+///
+/// println!("{}: {}", rng.name, rng.generate_name());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct LanguageSelector {
+    choices: Vec<(LanguageEntry, u32)>,
+}
+
+impl Default for LanguageSelector {
+    fn default() -> Self {
+        Language::one_per_family()
+            .into_iter()
+            .fold(LanguageSelector::new(), |selector, language| {
+                selector.with_language(language, 1)
+            })
+    }
+}
+
+impl LanguageSelector {
+    /// A selector with nothing to choose from.
+    #[must_use]
+    pub fn new() -> LanguageSelector {
+        LanguageSelector {
+            choices: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_language(self, language: Language, weight: u32) -> LanguageSelector {
+        self.with_entry(
+            LanguageEntry {
+                name: language.to_string(),
+                source: LanguageSource::BuiltIn(language),
+            },
+            weight,
+        )
+    }
+
+    /// Adds a language file, named after the file without its extension.
+    #[must_use]
+    pub fn with_file(self, path: impl Into<PathBuf>, weight: u32) -> LanguageSelector {
+        let path = path.into();
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        self.with_entry(
+            LanguageEntry {
+                name,
+                source: LanguageSource::File(path),
+            },
+            weight,
+        )
+    }
+
+    /// Adds a language, such as one found through a `LanguageRegistry`. Adding a language with
+    /// the same name as an existing choice replaces it, so its weight can be changed.
+    #[must_use]
+    pub fn with_entry(mut self, entry: LanguageEntry, weight: u32) -> LanguageSelector {
+        self = self.without(&entry.name);
+        self.choices.push((entry, weight));
+        self
+    }
+
+    /// Removes the language with that name, if it is one of the choices.
+    #[must_use]
+    pub fn without(mut self, name: &str) -> LanguageSelector {
+        self.choices
+            .retain(|(entry, _)| !same_name(&entry.name, name));
+        self
+    }
+
+    /// The languages to choose from, with their weights.
+    #[must_use]
+    pub fn choices(&self) -> &[(LanguageEntry, u32)] {
+        &self.choices
+    }
+
+    /// True if there is no language with a positive weight to choose from.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.choices.iter().all(|(_, weight)| *weight == 0)
+    }
+
+    /// Picks one of the languages, or `None` if there is nothing to choose from.
+    pub fn choose<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&LanguageEntry> {
+        let index = WeightedIndex::new(self.choices.iter().map(|(_, weight)| *weight)).ok()?;
+        Some(&self.choices[index.sample(rng)].0)
+    }
+
+    /// Picks one of the languages and loads it.
+    ///
+    /// # Errors
+    ///
+    /// Errors out with `RNGErrorKind::GenerationError` if there is nothing to choose from, or if
+    /// the chosen language file is not able to be processed correctly.
+    pub fn random(&self) -> Result<RNG, RNGError> {
+        self.choose(&mut rand::rng())
+            .ok_or(RNGErrorKind::GenerationError)?
+            .load()
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod selector_tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn default() {
+        let selector = LanguageSelector::default();
+        let names: Vec<&str> = selector
+            .choices()
+            .iter()
+            .map(|(entry, _)| entry.name.as_str())
+            .collect();
+
+        assert_eq!(selector.choices().len(), 6);
+        assert!(names.contains(&"Klingon"));
+        assert!(names.contains(&"Goblin"));
+        assert!(!names.contains(&"Гоблин"));
+        assert!(!names.contains(&"Curse"));
+        assert!(!names.contains(&"German-curse"));
+    }
+
+    #[test]
+    fn choose__respects_weights() {
+        let selector = LanguageSelector::new()
+            .with_language(Language::Elven, 3)
+            .with_language(Language::Goblin, 1);
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let elven = (0..4000)
+            .filter(|_| selector.choose(&mut rng).unwrap().name == "Elven")
+            .count();
+
+        assert!((2700..3300).contains(&elven), "{elven}");
+    }
+
+    #[test]
+    fn choose__zero_weight_is_never_chosen() {
+        let selector = LanguageSelector::new()
+            .with_language(Language::Elven, 0)
+            .with_language(Language::Goblin, 1);
+        let mut rng = StdRng::seed_from_u64(7);
+
+        assert!((0..100).all(|_| selector.choose(&mut rng).unwrap().name == "Goblin"));
+    }
+
+    #[test]
+    fn choose__empty() {
+        let mut rng = StdRng::seed_from_u64(7);
+
+        assert!(LanguageSelector::new().choose(&mut rng).is_none());
+        assert!(
+            LanguageSelector::new()
+                .with_language(Language::Elven, 0)
+                .choose(&mut rng)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn with_entry__replaces_existing_choice() {
+        let selector = LanguageSelector::new()
+            .with_language(Language::Elven, 1)
+            .with_language(Language::Elven, 5);
+
+        assert_eq!(selector.choices().len(), 1);
+        assert_eq!(selector.choices()[0].1, 5);
+    }
+
+    #[test]
+    fn without() {
+        let selector = LanguageSelector::default()
+            .without("klingon")
+            .without("goblin");
+
+        assert_eq!(selector.choices().len(), 4);
+        assert!(
+            selector
+                .choices()
+                .iter()
+                .all(|(entry, _)| entry.name != "Klingon")
+        );
+    }
+
+    #[test]
+    fn random() {
+        let rng = LanguageSelector::new()
+            .with_language(Language::Roman, 1)
+            .random()
+            .unwrap();
+
+        assert_eq!(rng, RNG::try_from(&Language::Roman).unwrap());
+    }
+
+    #[test]
+    fn random__file() {
+        let rng = LanguageSelector::new()
+            .with_file("src/languages/Test-micro.txt", 1)
+            .random()
+            .unwrap();
+
+        assert_eq!(rng.name, "Test-micro");
+    }
+
+    #[test]
+    fn random__empty() {
+        let error = LanguageSelector::new().random().unwrap_err();
+
+        assert!(LanguageSelector::new().is_empty());
+        assert_eq!(error.kind(), RNGErrorKind::GenerationError);
+    }
+}
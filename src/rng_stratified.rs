@@ -0,0 +1,131 @@
+use crate::RNG;
+use crate::rng_syllables::Syllables;
+
+/// Generates a batch of names that spreads out over a language's prefixes and suffixes. Each
+/// prefix and suffix is drawn without replacement until every one of them has been used, and
+/// then the pool is refilled, so a batch doesn't keep reusing the same few syllables.
+///
+/// Suffixes still have to join up with the syllable before them. When none of the unused
+/// suffixes do, one of the used ones is picked instead.
+pub(crate) struct Stratified<'a> {
+    language: &'a RNG,
+    prefixes: Syllables,
+    suffixes: Syllables,
+}
+
+impl<'a> Stratified<'a> {
+    pub(crate) fn new(language: &'a RNG) -> Stratified<'a> {
+        Stratified {
+            language,
+            prefixes: Syllables::new(),
+            suffixes: Syllables::new(),
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the language's prefix, center, or suffix syllable sets are empty or contain no
+    /// syllable compatible with the one before it.
+    pub(crate) fn syllables(&mut self, mut syllable_count: u8) -> Syllables {
+        if self.prefixes.is_empty() {
+            self.prefixes = self.language.prefixes.clone();
+        }
+        let mut last = self
+            .prefixes
+            .get_random()
+            .expect("language has at least one prefix syllable")
+            .clone();
+        self.prefixes.remove(&last.value);
+        let mut syllables = Syllables::new();
+        syllables.add(last.clone());
+
+        while syllable_count > 2 {
+            last = self.language.centers.next_from(&last);
+            syllables.add(last.clone());
+            syllable_count -= 1;
+        }
+
+        if self.suffixes.is_empty() {
+            self.suffixes = self.language.suffixes.clone();
+        }
+        let suffix = match self.suffixes.filter_from(last.jnext).get_random() {
+            Some(suffix) => {
+                self.suffixes.remove(&suffix.value);
+                suffix.clone()
+            }
+            None => self.language.suffixes.next_from(&last),
+        };
+        syllables.add(suffix);
+
+        syllables
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod stratified_tests {
+    use super::*;
+    use crate::Language;
+    use rstest::rstest;
+    use std::collections::HashSet;
+
+    #[rstest]
+    #[case(Language::Elven)]
+    #[case(Language::Goblin)]
+    #[case(Language::Fantasy)]
+    fn syllables__every_prefix_before_any_repeats(#[case] language: Language) {
+        let rng = RNG::try_from(&language).unwrap();
+        let mut stratified = Stratified::new(&rng);
+        let count = rng
+            .prefixes
+            .all()
+            .iter()
+            .map(|p| p.value.to_lowercase())
+            .collect::<HashSet<String>>()
+            .len();
+
+        let prefixes: HashSet<String> = (0..count)
+            .map(|_| stratified.syllables(3).first().unwrap().value.clone())
+            .collect();
+
+        assert_eq!(prefixes.len(), count);
+    }
+
+    #[test]
+    fn syllables__every_suffix_before_any_repeats() {
+        let rng = RNG::new_from_str("Micro".to_string(), "-ka\n-ri\nmo\n+zu\n+do\n+la").unwrap();
+        let mut stratified = Stratified::new(&rng);
+
+        let suffixes: HashSet<String> = (0..3)
+            .map(|_| stratified.syllables(2).last().unwrap().value.clone())
+            .collect();
+
+        assert_eq!(suffixes.len(), 3);
+    }
+
+    #[test]
+    fn syllables__refills() {
+        let rng = RNG::new_from_str("Micro".to_string(), "-ka\n-ri\nmo\n+zu\n+do").unwrap();
+        let mut stratified = Stratified::new(&rng);
+
+        let names: Vec<String> = (0..4).map(|_| stratified.syllables(3).collapse()).collect();
+        let first: HashSet<&String> = names[..2].iter().collect();
+        let second: HashSet<&String> = names[2..].iter().collect();
+
+        assert_eq!(first.len(), 2);
+        assert_eq!(second.len(), 2);
+    }
+
+    #[test]
+    fn syllables__obeys_joiners() {
+        let rng = RNG::try_from(&Language::Elven).unwrap();
+        let mut stratified = Stratified::new(&rng);
+
+        for _ in 0..200 {
+            let syllables = stratified.syllables(4);
+            for pair in syllables.all().windows(2) {
+                assert!(pair[0].connects(&pair[1]), "{syllables:?}");
+            }
+        }
+    }
+}
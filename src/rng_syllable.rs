@@ -3,8 +3,8 @@ use std::fmt;
 use std::str::FromStr;
 use std::sync::LazyLock;
 
-use crate::RNGError;
 use crate::rng_joiner::Joiner;
+use crate::{RNGError, RNGErrorKind};
 
 static _CONSONANTS: [char; 57] = [
     'b', 'ɓ', 'ʙ', 'β', 'c', 'd', 'ɗ', 'ɖ', 'ð', 'f', 'g', 'h', 'j', 'k', 'l', 'ł', 'm', 'ɱ', 'n',
@@ -21,7 +21,7 @@ static VOWELS: [char; 56] = [
 
 // https://regex101.com/r/UZ4REr/1
 static FULL_RE: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"^([-+]{0,1})([\p{Cyrillic}\p{Greek}\p{Arabic}\p{Hiragana}A-Za-zūæöäüß']+)\s*([\+\-][vcVC]){0,1}\s{0,1}([\+\-][vcVC]){0,1}$")
+    Regex::new(r"^([-+]{0,1})([\p{Cyrillic}\p{Greek}\p{Arabic}\p{Hiragana}\p{Latin}']+)\s*([\+\-][vcVC]){0,1}\s{0,1}([\+\-][vcVC]){0,1}\s*(?:\*(\d+))?$")
         .expect("FULL_RE is a valid regex")
 });
 static PREFIX_RE: LazyLock<Regex> =
@@ -63,12 +63,17 @@ static SUFFIX_RE: LazyLock<Regex> =
 /// 3) -v means that this syllable can only be added to another syllable, that ends with a vocal.
 /// 4) -c means that this syllable can only be added to another syllable, that ends with a consonant.
 ///
+/// WEIGHT:
+/// A syllable may end with "*" and a positive number (example: "aad +v *3") to make it that many times as likely to be
+/// chosen as a syllable without one. The default weight is 1.
+///
 #[derive(Clone, Debug, PartialEq)]
 pub struct Syllable {
     pub value: String,
     pub classification: Classification,
     pub jprevious: Joiner,
     pub jnext: Joiner,
+    pub weight: u32,
 }
 
 impl Syllable {
@@ -156,7 +161,11 @@ impl fmt::Display for Syllable {
             self.value,
             self.jprevious.value_previous(),
             self.jnext.value_next(),
-        )
+        )?;
+        if self.weight != 1 {
+            write!(f, " *{}", self.weight)?;
+        }
+        Ok(())
     }
 }
 
@@ -164,18 +173,26 @@ impl FromStr for Syllable {
     type Err = RNGError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if FULL_RE.is_match(s) {
-            let (classification, value) = Syllable::classify(s);
-            let syllable = Syllable {
-                value,
-                classification,
-                jnext: Syllable::determine_next_joiner(s),
-                jprevious: Syllable::determine_previous_joiner(s),
-            };
-            Ok(syllable)
-        } else {
-            Err(RNGError::ParsingError)
-        }
+        let error = || RNGError::new(RNGErrorKind::ParsingError).with_text(s);
+        let cap = FULL_RE.captures(s).ok_or_else(error)?;
+        let weight = match cap.get(5) {
+            Some(weight) => weight
+                .as_str()
+                .parse::<u32>()
+                .ok()
+                .filter(|weight| *weight > 0)
+                .ok_or_else(error)?,
+            None => 1,
+        };
+
+        let (classification, value) = Syllable::classify(s);
+        Ok(Syllable {
+            value,
+            classification,
+            jnext: Syllable::determine_next_joiner(s),
+            jprevious: Syllable::determine_previous_joiner(s),
+            weight,
+        })
     }
 }
 
@@ -271,6 +288,7 @@ mod syllable_tests {
             classification: Classification::Center,
             jnext: Joiner::SOME | Joiner::ONLY_VOWEL,
             jprevious: Joiner::SOME | Joiner::VOWEL | Joiner::ONLY_CONSONANT,
+            weight: 1,
         };
 
         let actual = Syllable::from_str("idr -c +v");
@@ -285,6 +303,7 @@ mod syllable_tests {
             classification: Classification::Prefix,
             jnext: Joiner::SOME,
             jprevious: Joiner::SOME | Joiner::VOWEL,
+            weight: 1,
         };
 
         let actual = Syllable::from_str("-asd");
@@ -299,6 +318,7 @@ mod syllable_tests {
             classification: Classification::Suffix,
             jprevious: Joiner::SOME | Joiner::VOWEL | Joiner::ONLY_VOWEL,
             jnext: Joiner::SOME | Joiner::VOWEL,
+            weight: 1,
         };
 
         let actual = Syllable::from_str("+adly -v");
@@ -306,6 +326,14 @@ mod syllable_tests {
         assert_eq!(expected, actual.unwrap());
     }
 
+    #[test]
+    fn new__phonemes() {
+        let actual = Syllable::from_str("-ʃɛŋ +v").unwrap();
+
+        assert_eq!(actual.value, "ʃɛŋ");
+        assert_eq!(actual.classification, Classification::Prefix);
+    }
+
     #[test]
     fn starts_with_vowel() {
         let actual = Syllable::from_str("+sadly -v");
@@ -390,13 +418,16 @@ mod syllable_tests {
         case("!"),
         case("+-"),
         case("++asda"),
-        case("+123asfd3ew")
+        case("+123asfd3ew"),
+        case("-ang *0"),
+        case("-ang *"),
+        case("-ang *99999999999")
     )]
     fn new__invalid__error(input: &str) {
-        assert_eq!(
-            Syllable::from_str(input).unwrap_err(),
-            RNGError::ParsingError
-        );
+        let error = Syllable::from_str(input).unwrap_err();
+
+        assert_eq!(error.kind(), RNGErrorKind::ParsingError);
+        assert_eq!(error.text(), Some(input));
     }
 
     #[rstest(input, case("!"), case("+-"), case("+123asfd3ew"))]
@@ -411,11 +442,25 @@ mod syllable_tests {
         case("+ean -c", "+ean -c".to_string()),
         case("+emar ", "+emar".to_string()),
         case("ladd  -v +v", "ladd -v +v".to_string()),
+        case("-ang +v *3", "-ang +v *3".to_string()),
+        case("ang*2", "ang *2".to_string()),
+        case("+ean *1", "+ean".to_string()),
     )]
     fn to_string(input: &str, expected: String) {
         assert_eq!(Syllable::from_str(input).unwrap().to_string(), expected);
     }
 
+    #[rstest(
+        input,
+        weight,
+        case("-ang", 1),
+        case("-ang +v *3", 3),
+        case("ладд -v *12", 12)
+    )]
+    fn new__weight(input: &str, weight: u32) {
+        assert_eq!(Syllable::from_str(input).unwrap().weight, weight);
+    }
+
     #[test]
     fn to_string_tmp() {
         let s = Syllable::from_str("-ang +v").unwrap();
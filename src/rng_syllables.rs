@@ -1,4 +1,5 @@
-use rand::distr::{Distribution, Uniform};
+use rand::Rng;
+use rand::distr::{Distribution, weighted::WeightedIndex};
 use std::str::FromStr;
 
 use crate::rng_joiner::Joiner;
@@ -29,6 +30,25 @@ impl Syllables {
         self.0.push(elem);
     }
 
+    /// Removes every syllable with the value passed in, ignoring case, returning how many were
+    /// removed.
+    pub fn remove(&mut self, value: &str) -> usize {
+        let value = value.to_lowercase();
+        let before = self.0.len();
+        self.0.retain(|s| s.value.to_lowercase() != value);
+        before - self.0.len()
+    }
+
+    /// Replaces the first syllable with the same value, ignoring case, or adds the syllable if
+    /// there is none.
+    pub fn replace(&mut self, syllable: Syllable) {
+        let value = syllable.value.to_lowercase();
+        match self.0.iter_mut().find(|s| s.value.to_lowercase() == value) {
+            Some(existing) => *existing = syllable,
+            None => self.0.push(syllable),
+        }
+    }
+
     pub fn all(&self) -> &Vec<Syllable> {
         &self.0
     }
@@ -64,7 +84,13 @@ impl Syllables {
     }
 
     pub fn get_random(&self) -> Option<&Syllable> {
-        self.0.get(self.rnd())
+        self.get_random_with(&mut rand::rng())
+    }
+
+    /// Picks a random syllable using the random number generator passed in, so that seeded
+    /// generators pick the same syllables every time.
+    pub fn get_random_with<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<&Syllable> {
+        self.0.get(self.rnd(rng))
     }
 
     pub fn is_empty(&self) -> bool {
@@ -86,17 +112,20 @@ impl Syllables {
             .clone()
     }
 
-    /// Generates a random value from the length of the Syllable Vector - 1.
-    /// <https://rust-lang-nursery.github.io/rust-cookbook/algorithms/randomness.html#generate-random-numbers-within-a-range/>
-    fn rnd(&self) -> usize {
-        let mut rng = rand::rng();
-        let length = self.len();
-        if length < 2 {
-            0
-        } else {
-            let die = Uniform::new(0, self.len() - 1).expect("range is non-empty");
-            die.sample(&mut rng)
+    /// Picks a random index into the Syllable Vector, each syllable in proportion to its weight.
+    fn rnd<R: Rng + ?Sized>(&self, rng: &mut R) -> usize {
+        if self.len() < 2 {
+            return 0;
         }
+        // Summed as u64, so that weights added up past u32::MAX still work.
+        WeightedIndex::new(self.0.iter().map(|s| u64::from(s.weight)))
+            .map(|die| die.sample(rng))
+            .unwrap_or_default()
+    }
+
+    /// The sum of the weights of every syllable.
+    pub fn total_weight(&self) -> u64 {
+        self.0.iter().map(|s| u64::from(s.weight)).sum()
     }
 }
 
@@ -252,14 +281,72 @@ mod syllables_tests {
         assert_eq!(actual, b);
     }
 
+    #[test]
+    fn get_random__weighted() {
+        let syllables = Syllables::new_from_array(&["ch *9", "abc"]);
+
+        let ch = (0..2000)
+            .filter(|_| syllables.get_random().unwrap().value == "ch")
+            .count();
+
+        assert!((1650..1950).contains(&ch), "{ch}");
+    }
+
+    #[test]
+    fn get_random__weights_past_u32_max() {
+        let syllables = Syllables::new_from_array(&["ch *4000000000", "abc *4000000000"]);
+
+        let ch = (0..2000)
+            .filter(|_| syllables.get_random().unwrap().value == "ch")
+            .count();
+
+        assert!((800..1200).contains(&ch), "{ch}");
+    }
+
+    #[test]
+    fn remove() {
+        let mut syllables = Syllables::new_from_array(&["ch", "abc", "Ch -v"]);
+
+        assert_eq!(syllables.remove("ch"), 2);
+        assert_eq!(syllables.remove("xyz"), 0);
+        assert_eq!(syllables, Syllables::new_from_array(&["abc"]));
+    }
+
+    #[test]
+    fn replace() {
+        let mut syllables = Syllables::new_from_array(&["Ch", "abc"]);
+
+        syllables.replace(Syllable::from_str("ch +v *4").unwrap());
+        syllables.replace(Syllable::from_str("er").unwrap());
+
+        assert_eq!(
+            syllables,
+            Syllables::new_from_array(&["ch +v *4", "abc", "er"])
+        );
+    }
+
+    #[test]
+    fn total_weight() {
+        assert_eq!(
+            Syllables::new_from_array(&["ch *9", "abc", "er *2"]).total_weight(),
+            12
+        );
+    }
+
     proptest! {
         #[test]
         fn rnd_test(_ in 0..20i32) {
             let c = Syllables::new_from_array(&["ch", "abc", "er", "go", "to"]);
-            let n = c.rnd();
+            let n = c.rnd(&mut rand::rng());
             assert!(n < c.len());
         }
 
+        #[test]
+        fn rnd_test__reaches_last(_ in 0..20i32) {
+            let c = Syllables::new_from_array(&["ch", "abc"]);
+            assert!((0..200).any(|_| c.rnd(&mut rand::rng()) == 1));
+        }
+
         #[test]
         fn get_random(_ in 0..20i32) {
             let syllables = Syllables::new_from_array(&["ch", "abc", "er", "go", "to"]);
@@ -0,0 +1,252 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use crate::rng_blend::usable;
+use crate::rng_syllable::Syllable;
+use crate::{LoadPolicy, RNG, RNGError};
+
+/// How many neighbours a syllable has to be seen with, all of them starting (or ending) the same
+/// way, before it is marked as only joining syllables like them.
+const MIN_EVIDENCE: usize = 2;
+
+/// Everything seen of one syllable in one position.
+struct Observed {
+    value: String,
+    count: u32,
+    /// Whether each syllable seen before it ended with a vowel.
+    previous: Vec<bool>,
+    /// Whether each syllable seen after it started with a vowel.
+    next: Vec<bool>,
+}
+
+/// Builds a language out of a corpus of example words, see `RNG::train`.
+pub(crate) fn train(name: String, corpus: &str) -> Result<RNG, RNGError> {
+    let words = words(corpus);
+    let onsets = onsets(&words);
+
+    let mut pools: [Vec<Observed>; 3] = [Vec::new(), Vec::new(), Vec::new()];
+    for word in &words {
+        let syllables = syllabify(word, &onsets);
+        if syllables.len() < 2 || !syllables.iter().all(|s| Syllable::from_str(s).is_ok()) {
+            continue;
+        }
+        let last = syllables.len() - 1;
+        for (i, syllable) in syllables.iter().enumerate() {
+            let position = match i {
+                0 => 0,
+                i if i == last => 2,
+                _ => 1,
+            };
+            let pool = &mut pools[position];
+            let index = pool
+                .iter()
+                .position(|o| o.value == *syllable)
+                .unwrap_or_else(|| {
+                    pool.push(Observed {
+                        value: syllable.clone(),
+                        count: 0,
+                        previous: Vec::new(),
+                        next: Vec::new(),
+                    });
+                    pool.len() - 1
+                });
+            let observed = &mut pool[index];
+            observed.count += 1;
+            if i > 0 {
+                observed
+                    .previous
+                    .push(Syllable::str_ends_with_vowel(&syllables[i - 1]));
+            }
+            if i < last {
+                observed
+                    .next
+                    .push(Syllable::str_starts_with_vowel(&syllables[i + 1]));
+            }
+        }
+    }
+
+    let mut file = String::new();
+    for (pool, classification) in pools.iter().zip(["-", "", "+"]) {
+        for observed in pool {
+            file.push_str(&line(classification, observed));
+            file.push('\n');
+        }
+    }
+    // Pools left empty are reported by `usable`, after pruning.
+    usable(RNG::load(name, &file, LoadPolicy::Lenient)?.0)
+}
+
+/// The language file line for the syllable.
+fn line(classification: &str, observed: &Observed) -> String {
+    let mut line = format!("{classification}{}", observed.value);
+    if let Some(vowel) = unanimous(&observed.previous) {
+        line.push_str(if vowel { " -v" } else { " -c" });
+    }
+    if let Some(vowel) = unanimous(&observed.next) {
+        line.push_str(if vowel { " +v" } else { " +c" });
+    }
+    if observed.count > 1 {
+        line = format!("{line} *{}", observed.count);
+    }
+    line
+}
+
+/// What every one of the observations agrees on, if there are enough of them.
+fn unanimous(observations: &[bool]) -> Option<bool> {
+    let first = *observations.first()?;
+    (observations.len() >= MIN_EVIDENCE && observations.iter().all(|o| *o == first))
+        .then_some(first)
+}
+
+/// The lowercase words in the corpus. Anything other than a letter or an apostrophe within a
+/// word separates words.
+fn words(corpus: &str) -> Vec<String> {
+    corpus
+        .split(|c: char| !c.is_alphabetic() && c != '\'')
+        .map(|word| word.trim_matches('\'').to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// The consonant clusters that words in the corpus start with. Any single consonant can also
+/// start a syllable.
+fn onsets(words: &[String]) -> HashSet<String> {
+    words
+        .iter()
+        .map(|word| {
+            word.chars()
+                .take_while(|c| !is_vowel(*c))
+                .collect::<String>()
+        })
+        .filter(|onset| onset.chars().count() > 1)
+        .collect()
+}
+
+/// Splits a word into syllables, one for each run of vowels. Of the consonants between two
+/// vowels, the longest cluster at the end that some word starts with goes with the second vowel,
+/// or else just the last consonant, and the rest stay with the first. A word without vowels is a
+/// single syllable.
+fn syllabify(word: &str, onsets: &HashSet<String>) -> Vec<String> {
+    let chars: Vec<char> = word.chars().collect();
+    let mut syllables = Vec::new();
+    let mut start = 0;
+    let mut i = chars
+        .iter()
+        .position(|c| is_vowel(*c))
+        .unwrap_or(chars.len());
+    loop {
+        while i < chars.len() && is_vowel(chars[i]) {
+            i += 1;
+        }
+        let consonants = i;
+        while i < chars.len() && !is_vowel(chars[i]) {
+            i += 1;
+        }
+        if i == chars.len() {
+            break;
+        }
+
+        let cluster = &chars[consonants..i];
+        let onset = (2..=cluster.len())
+            .rev()
+            .find(|n| onsets.contains(&cluster[cluster.len() - n..].iter().collect::<String>()))
+            .unwrap_or(1);
+        let end = i - onset.min(cluster.len());
+        syllables.push(chars[start..end].iter().collect());
+        start = end;
+    }
+    syllables.push(chars[start..].iter().collect());
+    syllables
+}
+
+fn is_vowel(c: char) -> bool {
+    Syllable::str_starts_with_vowel(c.encode_utf8(&mut [0; 4]))
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod train_tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn onsets_of(clusters: &[&str]) -> HashSet<String> {
+        clusters.iter().map(ToString::to_string).collect()
+    }
+
+    #[rstest]
+    #[case("banana", &[], vec!["ba", "na", "na"])]
+    #[case("garden", &[], vec!["gar", "den"])]
+    #[case("astra", &[], vec!["ast", "ra"])]
+    #[case("astra", &["str"], vec!["a", "stra"])]
+    #[case("oak", &[], vec!["oak"])]
+    #[case("crwth", &[], vec!["crwth"])]
+    #[case("aeon", &[], vec!["aeon"])]
+    #[case("эльфи", &[], vec!["эль", "фи"])]
+    fn syllabify(#[case] word: &str, #[case] clusters: &[&str], #[case] expected: Vec<&str>) {
+        assert_eq!(super::syllabify(word, &onsets_of(clusters)), expected);
+    }
+
+    #[test]
+    fn words() {
+        assert_eq!(
+            super::words("Q'onoS, 'kahless'\n# notes: ka-ri 42"),
+            vec!["q'onos", "kahless", "notes", "ka", "ri"]
+        );
+    }
+
+    #[test]
+    fn onsets() {
+        let words = vec!["stran".to_string(), "ka".to_string(), "tro".to_string()];
+
+        assert_eq!(super::onsets(&words), onsets_of(&["str", "tr"]));
+    }
+
+    #[test]
+    fn train() {
+        let rng = super::train(
+            "Micro".to_string(),
+            "kazuri kamori bazuro kazu bamo\nkazuri",
+        )
+        .unwrap();
+
+        assert_eq!(
+            rng.to_language_file(),
+            "-ka +c *4\n-ba +c *2\nzu -v +c *3\nmo\n+ri -v *3\n+ro\n+zu\n+mo\n"
+        );
+    }
+
+    #[test]
+    fn train__generates_from_the_corpus() {
+        let rng = super::train(
+            "Micro".to_string(),
+            "Valorian Caledor Menelar Taliesin Eldamar Aranel Galadon",
+        )
+        .unwrap();
+
+        for _ in 0..100 {
+            let name = rng.generate_name();
+            assert!(!rng.segment(&name).is_empty(), "{name}");
+        }
+    }
+
+    #[test]
+    fn train__too_few_syllables() {
+        let error = super::train("Micro".to_string(), "kazu bamo").unwrap_err();
+
+        assert_eq!(error.text(), Some("Micro has no usable prefix syllables"));
+    }
+
+    #[test]
+    fn train__round_trips_built_in_names() {
+        let goblin = RNG::try_from(&crate::Language::Goblin).unwrap();
+        let corpus = goblin.generate_names(200, false).join(" ");
+
+        let rng = super::train("Trained".to_string(), &corpus).unwrap();
+
+        assert!(!rng.prefixes.is_empty());
+        assert_eq!(
+            RNG::new_from_str("Trained".to_string(), &rng.to_language_file()).unwrap(),
+            rng
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// How Cyrillic is written in Latin letters, see `RNG::transliterated`.
+///
+/// ```
+/// use rnglib::Transliteration;
+///
+/// assert_eq!(Transliteration::Iso9.transliterate("Щукин"), "Ŝukin");
+/// assert_eq!(Transliteration::Gost.transliterate("Щукин"), "Shchukin");
+/// assert_eq!(Transliteration::Bgn.transliterate("Юрьев"), "Yuryev");
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Transliteration {
+    /// ISO 9, one Latin letter for each Cyrillic one, using diacritics such as `ž` and `ŝ`, so
+    /// that it can be reversed. The result isn't ASCII.
+    Iso9,
+    /// GOST R 52535.1-2006, as used in Russian passports: ASCII, with `ь` left out.
+    Gost,
+    /// BGN/PCGN, without its diacritics and the marks for `ъ` and `ь`, which are left out, so that
+    /// the result is ASCII. `е` is `ye` at the start of a word and after a vowel, `й`, `ъ` or `ь`,
+    /// and `e` everywhere else.
+    #[default]
+    Bgn,
+}
+
+impl Transliteration {
+    /// Writes out any Russian Cyrillic letters in the text in Latin letters, keeping their case
+    /// and leaving everything else as it is.
+    #[must_use]
+    pub fn transliterate(&self, text: &str) -> String {
+        self.transliterate_within(text, false)
+    }
+
+    /// Like `transliterate`, but for text that carries on a word, such as a syllable after the
+    /// start of a name, when `within` is set. The letter before the text isn't known, so a
+    /// BGN/PCGN `е` at its start is taken to follow a consonant, and is written `e`.
+    pub(crate) fn transliterate_within(self, text: &str, within: bool) -> String {
+        let table = match self {
+            Transliteration::Iso9 => &ISO_9,
+            Transliteration::Gost => &GOST,
+            Transliteration::Bgn => &BGN,
+        };
+        let mut latin = String::new();
+        let mut chars = text.chars().peekable();
+        let mut previous = None;
+        while let Some(c) = chars.next() {
+            let before = previous.replace(c);
+            let capitals = before.is_some_and(char::is_uppercase)
+                || chars.peek().is_some_and(|next| next.is_uppercase());
+            let lower = c.to_lowercase().next().unwrap_or(c);
+            let Some(i) = CYRILLIC.iter().position(|cyrillic| *cyrillic == lower) else {
+                latin.push(c);
+                continue;
+            };
+            let iotated = before.map_or(!within, iotates);
+            let letters = if self == Transliteration::Bgn && lower == 'е' && iotated {
+                "ye"
+            } else {
+                table[i]
+            };
+            if c == lower {
+                latin.push_str(letters);
+            } else if capitals {
+                // Part of a word in capitals, such as ЮЛЯ.
+                latin.push_str(&letters.to_uppercase());
+            } else {
+                let mut letters = letters.chars();
+                latin.extend(letters.next().into_iter().flat_map(char::to_uppercase));
+                latin.extend(letters);
+            }
+        }
+        latin
+    }
+}
+
+impl fmt::Display for Transliteration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Transliteration::Iso9 => write!(f, "ISO 9"),
+            Transliteration::Gost => write!(f, "GOST"),
+            Transliteration::Bgn => write!(f, "BGN/PCGN"),
+        }
+    }
+}
+
+impl FromStr for Transliteration {
+    type Err = BadTransliteration;
+
+    /// Parses a standard by name, ignoring case, spaces and anything after a slash, so `iso9`,
+    /// `ISO 9`, `gost`, `bgn` and `BGN/PCGN` are all accepted.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name: String = s
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .collect();
+        match name.as_str() {
+            "iso9" => Ok(Transliteration::Iso9),
+            "gost" => Ok(Transliteration::Gost),
+            "bgn" => Ok(Transliteration::Bgn),
+            _ => Err(BadTransliteration),
+        }
+    }
+}
+
+/// Returned when a string doesn't name a `Transliteration`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BadTransliteration;
+
+impl fmt::Display for BadTransliteration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid Transliteration")
+    }
+}
+
+impl std::error::Error for BadTransliteration {}
+
+/// True if a BGN/PCGN `е` after the character is written `ye`: at the start of a word, or after a
+/// vowel, `й`, `ъ` or `ь`.
+fn iotates(before: char) -> bool {
+    let lower = before.to_lowercase().next().unwrap_or(before);
+    !before.is_alphabetic() || "аеёиоуыэюяйъь".contains(lower)
+}
+
+/// The Russian alphabet, which each of the tables below follows letter by letter.
+static CYRILLIC: [char; 33] = [
+    'а', 'б', 'в', 'г', 'д', 'е', 'ё', 'ж', 'з', 'и', 'й', 'к', 'л', 'м', 'н', 'о', 'п', 'р', 'с',
+    'т', 'у', 'ф', 'х', 'ц', 'ч', 'ш', 'щ', 'ъ', 'ы', 'ь', 'э', 'ю', 'я',
+];
+
+static ISO_9: [&str; 33] = [
+    "a", "b", "v", "g", "d", "e", "ë", "ž", "z", "i", "j", "k", "l", "m", "n", "o", "p", "r", "s",
+    "t", "u", "f", "h", "c", "č", "š", "ŝ", "ʺ", "y", "ʹ", "è", "û", "â",
+];
+
+static GOST: [&str; 33] = [
+    "a", "b", "v", "g", "d", "e", "e", "zh", "z", "i", "i", "k", "l", "m", "n", "o", "p", "r", "s",
+    "t", "u", "f", "kh", "tc", "ch", "sh", "shch", "ie", "y", "", "e", "iu", "ia",
+];
+
+static BGN: [&str; 33] = [
+    "a", "b", "v", "g", "d", "e", "yo", "zh", "z", "i", "y", "k", "l", "m", "n", "o", "p", "r",
+    "s", "t", "u", "f", "kh", "ts", "ch", "sh", "shch", "", "y", "", "e", "yu", "ya",
+];
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod translit_tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Transliteration::Iso9, "Хрущёв", "Hruŝëv")]
+    #[case(Transliteration::Gost, "Хрущёв", "Khrushchev")]
+    #[case(Transliteration::Bgn, "Хрущёв", "Khrushchyov")]
+    #[case(Transliteration::Iso9, "Цой", "Coj")]
+    #[case(Transliteration::Gost, "Цой", "Tcoi")]
+    #[case(Transliteration::Bgn, "Цой", "Tsoy")]
+    #[case(Transliteration::Gost, "объявление", "obieiavlenie")]
+    #[case(Transliteration::Bgn, "объявление", "obyavleniye")]
+    #[case(Transliteration::Bgn, "Гоблин-Gob", "Goblin-Gob")]
+    #[case(Transliteration::Bgn, "ЮЛЯ", "YULYA")]
+    #[case(Transliteration::Bgn, "Юля", "Yulya")]
+    #[case(Transliteration::Bgn, "Елена", "Yelena")]
+    #[case(Transliteration::Bgn, "Андреев", "Andreyev")]
+    #[case(Transliteration::Bgn, "Соловьев", "Solovyev")]
+    #[case(Transliteration::Bgn, "Медведев", "Medvedev")]
+    #[case(Transliteration::Bgn, "Анна Еремина", "Anna Yeremina")]
+    #[case(Transliteration::Gost, "Елена", "Elena")]
+    fn transliterate(
+        #[case] standard: Transliteration,
+        #[case] text: &str,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(standard.transliterate(text), expected);
+    }
+
+    #[test]
+    fn transliterate_within() {
+        assert_eq!(Transliteration::Bgn.transliterate_within("ев", true), "ev");
+        assert_eq!(
+            Transliteration::Bgn.transliterate_within("ев", false),
+            "yev"
+        );
+        assert_eq!(
+            Transliteration::Bgn.transliterate_within("аев", true),
+            "ayev"
+        );
+    }
+
+    #[rstest]
+    #[case(Transliteration::Gost)]
+    #[case(Transliteration::Bgn)]
+    fn transliterate__ascii(#[case] standard: Transliteration) {
+        let alphabet: String = CYRILLIC.iter().collect();
+
+        assert!(standard.transliterate(&alphabet).is_ascii());
+        assert!(standard.transliterate(&alphabet.to_uppercase()).is_ascii());
+    }
+
+    #[rstest]
+    #[case("iso9", Transliteration::Iso9)]
+    #[case("ISO 9", Transliteration::Iso9)]
+    #[case("Gost", Transliteration::Gost)]
+    #[case("bgn", Transliteration::Bgn)]
+    #[case("BGN/PCGN", Transliteration::Bgn)]
+    fn from_str(#[case] input: &str, #[case] expected: Transliteration) {
+        assert_eq!(Transliteration::from_str(input), Ok(expected));
+    }
+
+    #[test]
+    fn from_str__invalid() {
+        assert_eq!(Transliteration::from_str("pinyin"), Err(BadTransliteration));
+        assert_eq!(Transliteration::from_str(""), Err(BadTransliteration));
+    }
+
+    #[test]
+    fn from_str__round_trips_display() {
+        for standard in [
+            Transliteration::Iso9,
+            Transliteration::Gost,
+            Transliteration::Bgn,
+        ] {
+            assert_eq!(
+                Transliteration::from_str(&standard.to_string()),
+                Ok(standard)
+            );
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use rand::Rng;
 use rand::distr::{Distribution, weighted::WeightedIndex};
 use std::sync::LazyLock;
 
@@ -17,10 +18,13 @@ pub struct WeightedRnd {
 
 impl WeightedRnd {
     pub fn random(&self) -> u8 {
+        self.random_with(&mut rand::rng())
+    }
+
+    pub fn random_with<R: Rng + ?Sized>(&self, rng: &mut R) -> u8 {
         let dist = WeightedIndex::new(self.weights.as_slice())
             .expect("weights are non-empty and positive");
-        let mut rng = rand::rng();
-        self.counts.as_slice()[dist.sample(&mut rng)]
+        self.counts.as_slice()[dist.sample(rng)]
     }
 }
 